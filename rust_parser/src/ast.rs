@@ -1,14 +1,18 @@
 use std::collections::HashMap;
+use std::fmt;
 
 use lazy_static::lazy_static;
 
+use num_bigint::BigInt;
+use num_traits::{ToPrimitive, Zero};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, PartialOrd, Hash)]
 pub enum Value {
     Str(String),
     Bool(bool),
-    Int(i64),
+    // ICFP integers are unbounded; several contest programs overflow a machine word.
+    Int(BigInt),
 }
 
 static TARGET: &'static str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789!\"#$%&'()*+,-./:;<=>?@[\\]^_`|~ \n";
@@ -47,29 +51,30 @@ impl Value {
         Value::Int(Value::decode_integer_body(encoded))
     }
 
-    pub fn encode_integer_body(mut i: i64) -> String {
+    pub fn encode_integer_body(mut i: BigInt) -> String {
         lazy_static! {
             static ref BASE94_CHARS: Vec<char> = (33..127).map(|c| c as u8 as char).collect();
         }
 
         // Edge case: if number is 0, return the corresponding base 94 character ('!')
-        if i == 0 {
+        if i.is_zero() {
             return BASE94_CHARS[0].to_string();
         }
 
         let mut result = Vec::new();
+        let base = BigInt::from(94);
 
-        while i > 0 {
-            let remainder = (i % 94) as usize;
+        while i > BigInt::zero() {
+            let remainder = (&i % &base).to_usize().unwrap();
             result.push(BASE94_CHARS[remainder]);
-            i /= 94;
+            i /= &base;
         }
 
         // Reverse the result because characters were appended in reverse order
         result.iter().rev().collect()
     }
 
-    pub fn decode_integer_body(encoded: &str) -> i64 {
+    pub fn decode_integer_body(encoded: &str) -> BigInt {
         lazy_static! {
             static ref CHAR_TO_VALUE: std::collections::HashMap<char, i64> = {
                 // Define the base 94 characters
@@ -84,12 +89,13 @@ impl Value {
             };
         }
 
-        let mut result = 0_i64;
-        let mut power = 1_i64;
+        let mut result = BigInt::zero();
+        let mut power = BigInt::from(1);
+        let base = BigInt::from(94);
         for ch in encoded.chars().rev() {
             if let Some(&value) = CHAR_TO_VALUE.get(&ch) {
-                result += value * power;
-                power *= 94;
+                result += BigInt::from(value) * &power;
+                power *= &base;
             } else {
                 panic!("character in base-94 encoding unrecognized when decoding integer body ({}): '{}'", encoded, ch);
             }
@@ -113,7 +119,7 @@ mod decode_test {
 
     #[test]
     fn integer() {
-        assert_eq!(1337_i64, Value::decode_integer_body("/6"));
+        assert_eq!(BigInt::from(1337), Value::decode_integer_body("/6"));
     }
 }
 
@@ -140,7 +146,7 @@ impl UnaryOp {
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, PartialOrd, Hash)]
 pub struct Unary {
     pub op: UnaryOp,
-    pub val: Value,
+    pub val: Box<Expr>,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, PartialOrd, Hash)]
@@ -254,3 +260,116 @@ pub enum Expr {
     If(If),
     Variable(Variable),
 }
+
+impl Expr {
+    /// Serializes this `Expr` back into the space-separated ICFP wire syntax, inverse to
+    /// `parser::parse`.
+    ///
+    /// `parser::parse` rewrites every lambda binder (and the variables that reference it) to a
+    /// globally unique negative "unique scope" id, so that substitution never needs alpha
+    /// renaming. Those negative ids aren't valid wire-format variable ids, so this walk assigns
+    /// each `Lambda` a fresh natural id as it's encountered and remembers the mapping for the
+    /// `Variable`s under it, producing a term that decodes back to an equivalent AST.
+    pub fn encode(&self) -> String {
+        let mut tokens = Vec::new();
+        let mut id_rewrites = HashMap::new();
+        let mut next_fresh_id = 0_i64;
+        self.encode_into(&mut tokens, &mut id_rewrites, &mut next_fresh_id);
+        tokens.join(" ")
+    }
+
+    fn encode_into(
+        &self,
+        tokens: &mut Vec<String>,
+        id_rewrites: &mut HashMap<i64, i64>,
+        next_fresh_id: &mut i64,
+    ) {
+        match self {
+            Expr::Value(Value::Str(s)) => tokens.push(format!("S{}", Value::encode_string(s))),
+            Expr::Value(Value::Bool(true)) => tokens.push("T".to_string()),
+            Expr::Value(Value::Bool(false)) => tokens.push("F".to_string()),
+            Expr::Value(Value::Int(i)) if *i >= BigInt::zero() => {
+                tokens.push(format!("I{}", Value::encode_integer_body(i.clone())))
+            }
+            Expr::Value(Value::Int(i)) => {
+                // The wire format has no negative integer literal; negation is a unary op.
+                tokens.push("U-".to_string());
+                tokens.push(format!("I{}", Value::encode_integer_body(-i.clone())));
+            }
+            Expr::Unary(Unary { op, val }) => {
+                tokens.push(format!("U{}", unary_op_char(op)));
+                val.encode_into(tokens, id_rewrites, next_fresh_id);
+            }
+            Expr::Binary(Binary { op, first, second }) => {
+                tokens.push(format!("B{}", op.as_str()));
+                first.encode_into(tokens, id_rewrites, next_fresh_id);
+                second.encode_into(tokens, id_rewrites, next_fresh_id);
+            }
+            Expr::If(If {
+                condition,
+                if_true,
+                if_false,
+            }) => {
+                tokens.push("?".to_string());
+                condition.encode_into(tokens, id_rewrites, next_fresh_id);
+                if_true.encode_into(tokens, id_rewrites, next_fresh_id);
+                if_false.encode_into(tokens, id_rewrites, next_fresh_id);
+            }
+            Expr::Lambda(Lambda { body, arg }) => {
+                let fresh_id = *next_fresh_id;
+                *next_fresh_id += 1;
+                id_rewrites.insert(*body, fresh_id);
+                tokens.push(format!("L{}", Value::encode_integer_body(BigInt::from(fresh_id))));
+                arg.encode_into(tokens, id_rewrites, next_fresh_id);
+            }
+            Expr::Variable(Variable(id)) => {
+                let resolved = id_rewrites.get(id).copied().unwrap_or(*id);
+                tokens.push(format!("v{}", Value::encode_integer_body(BigInt::from(resolved))));
+            }
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    /// Prints the ICFP wire syntax, i.e. `self.encode()`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.encode())
+    }
+}
+
+fn unary_op_char(op: &UnaryOp) -> char {
+    match op {
+        UnaryOp::Neg => '-',
+        UnaryOp::Not => '!',
+        UnaryOp::StrToInt => '#',
+        UnaryOp::IntToStr => '$',
+    }
+}
+
+#[cfg(test)]
+mod encode_test {
+    use super::*;
+
+    #[test]
+    fn round_trips_string_and_integer() {
+        let expr = Expr::Binary(Binary {
+            op: BinaryOp::Cat,
+            first: Box::new(Expr::Value(Value::decode_string("'%4}).$%8"))),
+            second: Box::new(Expr::Value(Value::Int(BigInt::from(1337)))),
+        });
+        assert_eq!("B. S'%4}).$%8 I/6", expr.encode());
+    }
+
+    #[test]
+    fn assigns_fresh_ids_to_rewritten_lambda_scopes() {
+        // Two nested lambdas, as `parser::parse` would produce with unique_scope ids -1, -2.
+        let expr = Expr::Lambda(Lambda {
+            body: -1,
+            arg: Box::new(Expr::Lambda(Lambda {
+                body: -2,
+                arg: Box::new(Expr::Variable(Variable(-1))),
+            })),
+        });
+        assert_eq!("L! L\" v!", expr.encode());
+    }
+}
@@ -19,9 +19,6 @@ struct Args {
     #[arg(long, default_value_t = false)]
     ast: bool,
 
-    #[arg(long, default_value_t = false)]
-    no_rewrite_id: bool,
-
     #[arg(long)]
     input: path::PathBuf,
 }
@@ -35,7 +32,71 @@ enum Command {
         #[arg(long, default_value_t = false)]
         no_show_vars: bool,
     },
-    Run,
+    Run {
+        /// Stop after this many reduction steps and write a resumable checkpoint instead of
+        /// running to completion.
+        #[arg(long)]
+        gas: Option<u64>,
+        #[arg(long, default_value = "checkpoint.json")]
+        checkpoint: path::PathBuf,
+    },
+    /// Resume a reduction previously paused by `run --gas`.
+    Resume {
+        #[arg(long, default_value = "checkpoint.json")]
+        checkpoint: path::PathBuf,
+        /// Stop again after this many further steps; omit to run to completion.
+        #[arg(long)]
+        gas: Option<u64>,
+    },
+    /// Evaluate using the Krivine machine backend instead of the tree-walking `Executor`.
+    RunFast,
+    /// Evaluate using the call-by-need backend, sharing thunks across duplicated references.
+    RunShared,
+    /// Evaluate by compiling to bytecode first and running it on the stack-based VM.
+    RunVm,
+    /// Print every subterm matched by a selector, e.g. `//binary[op=Apply]/first`.
+    Query { selector: String },
+    /// Reduce to a value like `run`, but print every intermediate expression along the way.
+    Trace {
+        #[arg(long, default_value_t = rust_parser::executor::DEFAULT_BUDGET)]
+        budget: u64,
+        /// Only print this many trace entries from the end, instead of the whole reduction.
+        #[arg(long)]
+        tail: Option<usize>,
+    },
+    /// Run the best-effort type checker and print the inferred type (or the first mismatch).
+    TypeCheck,
+}
+
+/// Runs `expr` for at most `gas` further steps, printing the result if it terminates or writing
+/// a resumable checkpoint (total step count included) to `checkpoint` if it doesn't.
+fn run_with_gas(
+    mut executor: rust_parser::executor::Executor,
+    expr: Box<Expr>,
+    gas: u64,
+    steps_already_taken: u64,
+    checkpoint: &path::PathBuf,
+) {
+    match executor.run_with_gas(expr, gas).unwrap() {
+        rust_parser::executor::GasResult::Done(val) => println!("Result: {:?}", val),
+        rust_parser::executor::GasResult::Paused {
+            remaining,
+            steps_taken,
+        } => {
+            let total_steps = steps_already_taken + steps_taken;
+            let saved = rust_parser::executor::Checkpoint {
+                expr: *remaining,
+                executor,
+                steps_taken: total_steps,
+            };
+            fs::write(checkpoint, serde_json::to_string(&saved).unwrap()).unwrap();
+            println!(
+                "Paused after {} total steps, checkpoint written to {}",
+                total_steps,
+                checkpoint.display()
+            );
+        }
+    }
 }
 
 fn main() {
@@ -52,13 +113,7 @@ fn main() {
 
         let parse_tree = parse_result.next().unwrap();
         let rewrites = BTreeMap::new();
-        rust_parser::parser::parse(
-            parse_tree,
-            &rewrites,
-            Rc::clone(&unique_scope),
-            !args.no_rewrite_id,
-        )
-        .unwrap()
+        rust_parser::parser::parse(parse_tree, &rewrites, Rc::clone(&unique_scope)).unwrap()
     } else {
         serde_json::from_str(&input_icfp).unwrap()
     };
@@ -77,39 +132,117 @@ fn main() {
             let mut executor = rust_parser::executor::Executor {
                 variables: HashMap::new(),
                 next_unique_scope: *unique_scope.borrow(),
+                budget: rust_parser::executor::DEFAULT_BUDGET,
             };
-            let mut next = executor.step(Box::new(ast));
+            let mut next = executor.step(Box::new(ast)).unwrap();
             let mut i = 1;
             while i < iterations {
-                next = executor.step(next);
+                next = executor.step(next).unwrap();
                 i += 1;
             }
             if !no_show_vars {
                 println!("Variables:");
-                let mut vars: Vec<(&i64, &Box<Expr>)> = executor.variables.iter().collect();
-                vars.sort_by(|first, second| second.partial_cmp(first).unwrap());
+                let mut vars: Vec<(&i64, &rust_parser::executor::ThunkCell)> =
+                    executor.variables.iter().collect();
+                vars.sort_by_key(|(id, _)| std::cmp::Reverse(**id));
                 for (k, v) in vars {
-                    println!("V{}: {}", k, v)
+                    println!("V{}: {:?}", k, v.borrow())
                 }
             }
             println!("AST: {}", &next);
         }
-        Command::Run => {
-            let mut executor = rust_parser::executor::Executor {
+        Command::Run { gas, checkpoint } => {
+            let executor = rust_parser::executor::Executor {
                 variables: HashMap::new(),
                 next_unique_scope: *unique_scope.borrow(),
+                budget: rust_parser::executor::DEFAULT_BUDGET,
             };
-            let mut next = executor.step(Box::new(ast));
-            let max_iterations = 1_000_000_000;
-            let mut i = 1;
-            while i < max_iterations {
-                next = executor.step(next);
-                if let Expr::Value(_) = next.as_ref() {
-                    break;
+            match gas {
+                Some(budget) => run_with_gas(executor, Box::new(ast), budget, 0, &checkpoint),
+                None => {
+                    let mut executor = executor;
+                    let mut next = executor.step(Box::new(ast)).unwrap();
+                    let max_iterations = 1_000_000_000;
+                    let mut i = 1;
+                    while i < max_iterations {
+                        next = executor.step(next).unwrap();
+                        if let Expr::Value(_) = next.as_ref() {
+                            break;
+                        }
+                        i += 1;
+                    }
+                    println!("AST: {}", &next);
                 }
-                i += 1;
             }
-            println!("Result: {}", &next);
         }
+        Command::Resume { checkpoint, gas } => {
+            let data = fs::read_to_string(&checkpoint).unwrap();
+            let loaded: rust_parser::executor::Checkpoint = serde_json::from_str(&data).unwrap();
+            match gas {
+                Some(budget) => run_with_gas(
+                    loaded.executor,
+                    Box::new(loaded.expr),
+                    budget,
+                    loaded.steps_taken,
+                    &checkpoint,
+                ),
+                None => {
+                    let mut executor = loaded.executor;
+                    let val = executor.fully_evaluate(Box::new(loaded.expr)).unwrap();
+                    println!("Result: {:?}", val);
+                }
+            }
+        }
+        Command::RunFast => {
+            let env = rust_parser::krivine::Env::new();
+            let closure = rust_parser::krivine::Closure::new(Rc::new(ast), env);
+            let result = rust_parser::krivine::Krivine::new().run(closure);
+            println!("Result: {:?}", result);
+        }
+        Command::RunShared => {
+            let env = rust_parser::need::Env::new();
+            let result = rust_parser::need::NeedExecutor::new()
+                .fully_evaluate(ast, env)
+                .unwrap();
+            println!("Result: {:?}", result);
+        }
+        Command::RunVm => {
+            let program = rust_parser::vm::compile(&ast);
+            let result = rust_parser::vm::run(&program).unwrap();
+            println!("Result: {:?}", result);
+        }
+        Command::Query { selector } => {
+            let selector = rust_parser::selector::Selector::parse(&selector).unwrap();
+            for (path, matched) in selector.select(&ast) {
+                println!("{}: {:#?}", path, matched);
+            }
+        }
+        Command::Trace { budget, tail } => {
+            let mut executor = rust_parser::executor::Executor {
+                variables: HashMap::new(),
+                next_unique_scope: *unique_scope.borrow(),
+                budget: rust_parser::executor::DEFAULT_BUDGET,
+            };
+            let (result, trace) = executor.reduce_with_trace(Box::new(ast), budget);
+            let shown = match tail {
+                Some(n) => &trace[trace.len().saturating_sub(n)..],
+                None => &trace[..],
+            };
+            for entry in shown {
+                println!("{}: {}", entry.step, entry.redex);
+            }
+            match result {
+                Ok(val) => println!("Result: {:?}", val),
+                Err(err) => println!(
+                    "Error after {} steps: {}",
+                    trace.last().map_or(0, |entry| entry.step),
+                    err
+                ),
+            }
+        }
+        Command::TypeCheck => match rust_parser::typecheck::check(&ast) {
+            Ok(ty) => println!("Type: {}", ty),
+            Err(err) => println!("Type error: {}", err),
+        },
     }
 }
@@ -1,216 +1,346 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+use num_traits::{ToPrimitive, Zero};
+use serde::{Deserialize, Serialize};
 
 use crate::ast::*;
 
+/// A bound argument, forced at most once: `Apply` stores the argument unevaluated, and the first
+/// `Variable` lookup that forces it overwrites the cell with its weak head normal form so every
+/// later reference to the same binding reuses the cached result instead of re-reducing it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Thunk {
+    Unevaluated(Box<Expr>),
+    Evaluated(Box<Expr>),
+}
+
+pub type ThunkCell = Rc<RefCell<Thunk>>;
+
+/// The step budget callers give `Executor::budget` when they have no task-specific one; it is
+/// the limit `maximally_evaluate`/`fully_evaluate` used before it became configurable.
+pub const DEFAULT_BUDGET: u64 = 1_000_000;
+
+#[derive(Serialize, Deserialize)]
 pub struct Executor {
-    pub variables: HashMap<i64, Box<Expr>>,
+    #[serde(with = "thunk_cells")]
+    pub variables: HashMap<i64, ThunkCell>,
+    pub next_unique_scope: i64,
+    /// Maximum number of `step` calls `maximally_evaluate`/`fully_evaluate` will take before
+    /// giving up with `EvalError::IterationLimitExceeded`.
+    pub budget: u64,
+}
+
+/// `Rc<RefCell<Thunk>>` has no `Serialize`/`Deserialize` impl of its own, so a checkpoint instead
+/// snapshots each thunk's current `Expr` and rebuilds fresh cells on load — sharing between
+/// duplicated references is only an in-process memoization optimization, not something a
+/// checkpoint needs to preserve.
+mod thunk_cells {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::rc::Rc;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{Thunk, ThunkCell};
+
+    pub fn serialize<S>(cells: &HashMap<i64, ThunkCell>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let snapshot: HashMap<i64, Thunk> =
+            cells.iter().map(|(id, cell)| (*id, cell.borrow().clone())).collect();
+        snapshot.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<HashMap<i64, ThunkCell>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let snapshot = HashMap::<i64, Thunk>::deserialize(deserializer)?;
+        Ok(snapshot
+            .into_iter()
+            .map(|(id, thunk)| (id, Rc::new(RefCell::new(thunk))))
+            .collect())
+    }
+}
+
+/// One recorded step of a `reduce_with_trace` run: how many reductions had been applied so far,
+/// and a compact (non-recursive) rendering of the expression at that point. Kept deliberately
+/// shallow so a non-terminating program's trace can be inspected (e.g. its last few entries)
+/// without the `{:#?}` dump of a reduction's full subtree blowing up the output.
+#[derive(Debug, Clone)]
+pub struct TraceEntry {
+    pub step: u64,
+    pub redex: String,
+}
+
+/// A fully self-contained snapshot of an in-progress `run_with_gas` reduction: the remaining
+/// continuation plus everything `Executor` needs to keep stepping it, so `--resume` can pick up
+/// exactly where `--gas` left off.
+#[derive(Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub expr: Expr,
+    pub executor: Executor,
+    pub steps_taken: u64,
+}
+
+/// Outcome of running a bounded number of reduction steps.
+pub enum GasResult {
+    Done(Value),
+    Paused { remaining: Box<Expr>, steps_taken: u64 },
+}
+
+/// The shape of a `Value`, independent of its contents, for reporting type errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueType {
+    Str,
+    Bool,
+    Int,
+    Fun,
+}
+
+impl fmt::Display for ValueType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ValueType::Str => "Str",
+            ValueType::Bool => "Bool",
+            ValueType::Int => "Int",
+            ValueType::Fun => "Fun",
+        };
+        write!(f, "{}", name)
+    }
 }
 
+fn value_type(val: &Value) -> ValueType {
+    match val {
+        Value::Str(_) => ValueType::Str,
+        Value::Bool(_) => ValueType::Bool,
+        Value::Int(_) => ValueType::Int,
+    }
+}
+
+/// Every way evaluation can fail, in place of the ad-hoc `panic!`s the tree-walker used to raise.
+#[derive(Debug)]
+pub enum EvalError {
+    TypeMismatch {
+        op: String,
+        expected: ValueType,
+        actual: ValueType,
+    },
+    UnboundVariable(i64),
+    DivisionByZero,
+    NonBooleanCondition(ValueType),
+    IterationLimitExceeded,
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::TypeMismatch {
+                op,
+                expected,
+                actual,
+            } => write!(f, "`{}` expected a {} but found a {}", op, expected, actual),
+            EvalError::UnboundVariable(id) => write!(f, "unbound variable: {}", id),
+            EvalError::DivisionByZero => write!(f, "division by zero"),
+            EvalError::NonBooleanCondition(actual) => {
+                write!(f, "`if` expected a Bool condition but found a {}", actual)
+            }
+            EvalError::IterationLimitExceeded => write!(f, "iteration limit exceeded"),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
 impl Executor {
-    pub fn step(&mut self, pgm: Box<Expr>) -> Box<Expr> {
+    /// Steps `expr` at most `gas` times, stopping early once it reduces to a terminal `Value`.
+    /// Unlike `fully_evaluate`, running out of budget is not an error here — it just reports
+    /// how far it got so the caller can checkpoint and resume later.
+    pub fn run_with_gas(&mut self, mut expr: Box<Expr>, gas: u64) -> Result<GasResult, EvalError> {
+        let mut steps_taken = 0;
+        loop {
+            if matches!(expr.as_ref(), Expr::Value(_)) {
+                return match *expr {
+                    Expr::Value(val) => Ok(GasResult::Done(val)),
+                    _ => unreachable!(),
+                };
+            }
+            if steps_taken >= gas {
+                return Ok(GasResult::Paused {
+                    remaining: expr,
+                    steps_taken,
+                });
+            }
+            expr = self.step(expr)?;
+            steps_taken += 1;
+        }
+    }
+
+    // `Expr`'s own variants hold their children as `Box<Expr>`, so every caller already has one
+    // to hand over and every return value is reboxed before going back into the tree; taking
+    // `Expr` by value here would just move the allocation to the call site instead of removing
+    // it. clippy::boxed_local doesn't see that and flags the parameter anyway.
+    #[allow(clippy::boxed_local)]
+    pub fn step(&mut self, pgm: Box<Expr>) -> Result<Box<Expr>, EvalError> {
         // println!("Step evaluating: {:#?}", pgm);
         match *pgm {
-            Expr::Value(val) => Box::new(Expr::Value(val)),
-            Expr::Unary(Unary { op, val: expr }) => match *expr {
-                Expr::Value(val) => self.eval_unary(op, val),
-                _ => Box::new(Expr::Unary(Unary {
-                    op,
-                    val: self.step(expr),
-                })),
-            },
+            Expr::Value(val) => Ok(Box::new(Expr::Value(val))),
+            Expr::Unary(Unary { op, val }) => self.eval_unary(op, val),
             Expr::Binary(Binary { op, first, second }) => self.eval_binary(op, first, second),
-            Expr::Lambda(lambda) => Box::new(Expr::Lambda(lambda)),
+            Expr::Lambda(lambda) => Ok(Box::new(Expr::Lambda(lambda))),
             Expr::If(If {
                 condition,
                 if_true,
                 if_false,
             }) => self.eval_if(condition, if_true, if_false),
-            Expr::Variable(Variable(id)) => {
-                let expr = self
-                    .variables
-                    .get(&id)
-                    .expect("ID not yet present at variable evaluation time")
-                    .clone();
-                // println!("Variable evaluated to {:#?}", expr);
-                expr
-            }
+            Expr::Variable(Variable(id)) => self.force(id),
         }
     }
 
-    pub fn eval_binary(&mut self, op: BinaryOp, first: Box<Expr>, second: Box<Expr>) -> Box<Expr> {
+    /// Forces the thunk bound to `id` to weak head normal form, memoizing the result so every
+    /// other reference to the same binding reuses it instead of re-reducing the argument.
+    fn force(&mut self, id: i64) -> Result<Box<Expr>, EvalError> {
+        let cell = self
+            .variables
+            .get(&id)
+            .ok_or(EvalError::UnboundVariable(id))?
+            .clone();
+        let unevaluated = match &*cell.borrow() {
+            Thunk::Evaluated(whnf) => return Ok(whnf.clone()),
+            Thunk::Unevaluated(expr) => expr.clone(),
+        };
+        let whnf = self.maximally_evaluate(unevaluated)?;
+        *cell.borrow_mut() = Thunk::Evaluated(whnf.clone());
+        Ok(whnf)
+    }
+
+    // See the comment on `step`: `first`/`second` come from and go back into `Binary`'s
+    // `Box<Expr>` fields, so the box is structural, not an avoidable local allocation.
+    #[allow(clippy::boxed_local)]
+    pub fn eval_binary(
+        &mut self,
+        op: BinaryOp,
+        first: Box<Expr>,
+        second: Box<Expr>,
+    ) -> Result<Box<Expr>, EvalError> {
         match op {
             BinaryOp::Apply => match *first {
                 Expr::Lambda(Lambda { body, arg }) => {
-                    self.variables.insert(body, second);
-                    arg
+                    self.variables
+                        .insert(body, Rc::new(RefCell::new(Thunk::Unevaluated(second))));
+                    Ok(arg)
                 }
-                Expr::Value(val) => panic!("Apply operator received a non-lambda value: {:?}", val),
-                val @ _ => Box::new(Expr::Binary(Binary {
+                Expr::Value(val) => Err(EvalError::TypeMismatch {
+                    op: "Apply".to_string(),
+                    expected: ValueType::Fun,
+                    actual: value_type(&val),
+                }),
+                other => Ok(Box::new(Expr::Binary(Binary {
                     op,
-                    first: self.step(Box::new(val)),
+                    first: self.step(Box::new(other))?,
                     second,
-                })),
+                }))),
             },
             _ => match (*first, *second) {
-                (Expr::Value(first_val), Expr::Value(second_val)) => match op {
-                    BinaryOp::Add => match (first_val, second_val) {
-                        (Value::Int(one), Value::Int(two)) => {
-                            Box::new(Expr::Value(Value::Int(one + two)))
-                        }
-                        _ => panic!("Addition operator received a non-integer value"),
-                    },
-                    BinaryOp::Sub => match (first_val, second_val) {
-                        (Value::Int(one), Value::Int(two)) => {
-                            Box::new(Expr::Value(Value::Int(one - two)))
-                        }
-                        _ => panic!("Subtraction operator received a non-integer value"),
-                    },
-                    BinaryOp::Mult => match (first_val, second_val) {
-                        (Value::Int(one), Value::Int(two)) => {
-                            Box::new(Expr::Value(Value::Int(one * two)))
-                        }
-                        _ => panic!("Multiplication operator received a non-integer value"),
-                    },
-                    BinaryOp::Div => match (first_val, second_val) {
-                        (Value::Int(one), Value::Int(two)) => {
-                            Box::new(Expr::Value(Value::Int(one / two)))
-                        }
-                        _ => panic!("Dividing operator received a non-integer value"),
-                    },
-                    BinaryOp::Mod => match (first_val, second_val) {
-                        (Value::Int(one), Value::Int(two)) => {
-                            Box::new(Expr::Value(Value::Int(one % two)))
-                        }
-                        _ => panic!("Modulo operator received a non-integer value"),
-                    },
-                    BinaryOp::Lt => match (first_val, second_val) {
-                        (Value::Int(one), Value::Int(two)) => {
-                            Box::new(Expr::Value(Value::Bool(one < two)))
-                        }
-                        _ => panic!("Less than operator received a non-integer value"),
-                    },
-                    BinaryOp::Gt => match (first_val, second_val) {
-                        (Value::Int(one), Value::Int(two)) => {
-                            Box::new(Expr::Value(Value::Bool(one > two)))
-                        }
-                        _ => panic!("Great than operator received a non-integer value"),
-                    },
-                    BinaryOp::Eq => match (first_val, second_val) {
-                        (Value::Int(one), Value::Int(two)) => {
-                            Box::new(Expr::Value(Value::Bool(one == two)))
-                        }
-                        (Value::Bool(one), Value::Bool(two)) => {
-                            Box::new(Expr::Value(Value::Bool(one == two)))
-                        }
-                        (Value::Str(one), Value::Str(two)) => {
-                            Box::new(Expr::Value(Value::Bool(one == two)))
-                        }
-                        _ => panic!("Equality operator received a non-integer value"),
-                    },
-                    BinaryOp::Or => match (first_val, second_val) {
-                        (Value::Bool(one), Value::Bool(two)) => {
-                            Box::new(Expr::Value(Value::Bool(one | two)))
-                        }
-                        _ => panic!("Or operator received a non-boolean value"),
-                    },
-                    BinaryOp::And => match (first_val, second_val) {
-                        (Value::Bool(one), Value::Bool(two)) => {
-                            Box::new(Expr::Value(Value::Bool(one & two)))
-                        }
-                        _ => panic!("And operator received a non-boolean value"),
-                    },
-                    BinaryOp::Cat => match (first_val, second_val) {
-                        (Value::Str(one), Value::Str(two)) => {
-                            Box::new(Expr::Value(Value::Str(format!("{}{}", one, two))))
-                        }
-                        _ => panic!("Concatenation operator received a non-string value"),
-                    },
-                    BinaryOp::Take => match (first_val, second_val) {
-                        (Value::Int(one), Value::Str(two)) => {
-                            Box::new(Expr::Value(Value::Str(two[..(one as usize)].to_string())))
-                        }
-                        _ => panic!("Take operator received a the wrong types"),
-                    },
-                    BinaryOp::Drop => match (first_val, second_val) {
-                        (Value::Int(one), Value::Str(two)) => {
-                            Box::new(Expr::Value(Value::Str(two[(one as usize)..].to_string())))
-                        }
-                        _ => panic!("Drop operator received a the wrong types"),
-                    },
-                    BinaryOp::Apply => unreachable!(),
-                },
-                (Expr::Value(first_val), second) => {
-                    return Box::new(Expr::Binary(Binary {
-                        op,
-                        first: Box::new(Expr::Value(first_val)),
-                        second: self.step(Box::new(second)),
-                    }))
-                }
-                (first, second) => {
-                    return Box::new(Expr::Binary(Binary {
-                        op,
-                        first: self.step(Box::new(first)),
-                        second: Box::new(second),
-                    }))
+                (Expr::Value(first_val), Expr::Value(second_val)) => {
+                    eval_strict_binary(op, first_val, second_val).map(|val| Box::new(Expr::Value(val)))
                 }
+                (Expr::Value(first_val), second) => Ok(Box::new(Expr::Binary(Binary {
+                    op,
+                    first: Box::new(Expr::Value(first_val)),
+                    second: self.step(Box::new(second))?,
+                }))),
+                (first, second) => Ok(Box::new(Expr::Binary(Binary {
+                    op,
+                    first: self.step(Box::new(first))?,
+                    second: Box::new(second),
+                }))),
             },
         }
     }
 
-    pub fn maximally_evaluate(&mut self, expression: Box<Expr>) -> Box<Expr> {
-        let mut next = self.step(expression);
-        let mut max_iter = 1_000_000;
-        while max_iter > 0 {
+    pub fn maximally_evaluate(&mut self, expression: Box<Expr>) -> Result<Box<Expr>, EvalError> {
+        let mut next = self.step(expression)?;
+        let mut remaining = self.budget;
+        while remaining > 0 {
             match *next {
-                Expr::Value(_) | Expr::Lambda(_) | Expr::Variable(_) => return next,
+                Expr::Value(_) | Expr::Lambda(_) | Expr::Variable(_) => return Ok(next),
                 _ => (),
             }
-            next = self.step(next);
-            max_iter -= 1;
+            next = self.step(next)?;
+            remaining -= 1;
         }
-        panic!("Maximal evaluation failed to resolve to a valid semi-terminal value: final state = {:?}", next)
+        Err(EvalError::IterationLimitExceeded)
     }
 
-    pub fn fully_evaluate(&mut self, expression: Box<Expr>) -> Value {
-        let mut next = self.step(expression);
-        let max_iter = 1_000_000;
+    pub fn fully_evaluate(&mut self, expression: Box<Expr>) -> Result<Value, EvalError> {
+        let mut next = self.step(expression)?;
         let mut i = 0;
-        while i < max_iter {
-            match *next {
-                Expr::Value(val) => return val,
-                _ => (),
+        while i < self.budget {
+            if let Expr::Value(val) = *next {
+                return Ok(val);
             }
-            next = self.step(next);
+            next = self.step(next)?;
             i += 1;
         }
-        panic!(
-            "Full evaluation failed to resolve to a terminal value: final state = {:#?}",
-            next
-        )
+        Err(EvalError::IterationLimitExceeded)
     }
 
-    pub fn eval_unary(&mut self, op: UnaryOp, val: Value) -> Box<Expr> {
-        match op {
-            UnaryOp::Neg => match val {
-                Value::Int(i) => Box::new(Expr::Value(Value::Int(-i))),
-                _ => panic!("Negation operator received a non-integer value"),
-            },
-            UnaryOp::Not => match val {
-                Value::Bool(b) => Box::new(Expr::Value(Value::Bool(!b))),
-                _ => panic!("Negation operator received a non-boolean value"),
-            },
-            UnaryOp::StrToInt => match val {
-                Value::Str(s) => Box::new(Expr::Value(Value::Int(Value::decode_integer_body(
-                    &Value::encode_string(&s),
-                )))),
-                _ => panic!("Str to str operator received a non-string value"),
-            },
-            UnaryOp::IntToStr => match val {
-                Value::Int(s) => Box::new(Expr::Value(Value::decode_string(
-                    &Value::encode_integer_body(s),
-                ))),
-                _ => panic!("Int to str operator received a non-integer value"),
-            },
+    /// Like `fully_evaluate`, but returns the full reduction sequence alongside the outcome
+    /// instead of only the final `Value` — a stepping debugger built on `step` without having to
+    /// re-derive every intermediate expression by hand. Each `TraceEntry` is recorded *before*
+    /// the step it describes is taken, so the last entry always shows the expression `step`
+    /// failed to make progress past (useful for diagnosing non-termination: callers can report
+    /// just the tail of the trace instead of the giant final `{:#?}`).
+    pub fn reduce_with_trace(
+        &mut self,
+        mut expr: Box<Expr>,
+        budget: u64,
+    ) -> (Result<Value, EvalError>, Vec<TraceEntry>) {
+        let mut trace = Vec::new();
+        let mut step_count = 0;
+        loop {
+            trace.push(TraceEntry {
+                step: step_count,
+                redex: render_compact(&expr),
+            });
+            if matches!(expr.as_ref(), Expr::Value(_)) {
+                return match *expr {
+                    Expr::Value(val) => (Ok(val), trace),
+                    _ => unreachable!(),
+                };
+            }
+            if step_count >= budget {
+                return (Err(EvalError::IterationLimitExceeded), trace);
+            }
+            expr = match self.step(expr) {
+                Ok(next) => next,
+                Err(err) => return (Err(err), trace),
+            };
+            step_count += 1;
+        }
+    }
+
+    /// Unlike the strict binary operators, a unary operator's operand is a full `Expr` (`U-` can
+    /// be applied to e.g. `B+ I1 I2`, not just a literal), so this reduces it one step at a time
+    /// the same way `eval_binary` reduces `first`/`second`, only applying the operator once the
+    /// operand has reached `Expr::Value`.
+    // See the comment on `step`: `val` comes from and goes back into `Unary::val`'s `Box<Expr>`
+    // field, so the box is structural, not an avoidable local allocation.
+    #[allow(clippy::boxed_local)]
+    pub fn eval_unary(&mut self, op: UnaryOp, val: Box<Expr>) -> Result<Box<Expr>, EvalError> {
+        match *val {
+            Expr::Value(val) => {
+                eval_strict_unary(&op, val).map(|result| Box::new(Expr::Value(result)))
+            }
+            other => Ok(Box::new(Expr::Unary(Unary {
+                op,
+                val: self.step(Box::new(other))?,
+            }))),
         }
     }
 
@@ -219,22 +349,159 @@ impl Executor {
         condition: Box<Expr>,
         if_true: Box<Expr>,
         if_false: Box<Expr>,
-    ) -> Box<Expr> {
-        match self.fully_evaluate(condition) {
-            Value::Bool(val) => {
-                if val {
-                    if_true
-                } else {
-                    if_false
-                }
+    ) -> Result<Box<Expr>, EvalError> {
+        match self.fully_evaluate(condition)? {
+            Value::Bool(true) => Ok(if_true),
+            Value::Bool(false) => Ok(if_false),
+            other => Err(EvalError::NonBooleanCondition(value_type(&other))),
+        }
+    }
+}
+
+fn eval_strict_unary(op: &UnaryOp, val: Value) -> Result<Value, EvalError> {
+    match (op, val) {
+        (UnaryOp::Neg, Value::Int(i)) => Ok(Value::Int(-i)),
+        (UnaryOp::Neg, other) => Err(EvalError::TypeMismatch {
+            op: "Neg".to_string(),
+            expected: ValueType::Int,
+            actual: value_type(&other),
+        }),
+        (UnaryOp::Not, Value::Bool(b)) => Ok(Value::Bool(!b)),
+        (UnaryOp::Not, other) => Err(EvalError::TypeMismatch {
+            op: "Not".to_string(),
+            expected: ValueType::Bool,
+            actual: value_type(&other),
+        }),
+        (UnaryOp::StrToInt, Value::Str(s)) => Ok(Value::Int(Value::decode_integer_body(
+            &Value::encode_string(&s),
+        ))),
+        (UnaryOp::StrToInt, other) => Err(EvalError::TypeMismatch {
+            op: "StrToInt".to_string(),
+            expected: ValueType::Str,
+            actual: value_type(&other),
+        }),
+        (UnaryOp::IntToStr, Value::Int(i)) => Ok(Value::decode_string(&Value::encode_integer_body(i))),
+        (UnaryOp::IntToStr, other) => Err(EvalError::TypeMismatch {
+            op: "IntToStr".to_string(),
+            expected: ValueType::Int,
+            actual: value_type(&other),
+        }),
+    }
+}
+
+fn eval_strict_binary(op: BinaryOp, first: Value, second: Value) -> Result<Value, EvalError> {
+    let op_name = op.as_str();
+    match op {
+        BinaryOp::Add => match (first, second) {
+            (Value::Int(one), Value::Int(two)) => Ok(Value::Int(one + two)),
+            (first, second) => mismatch(op_name, ValueType::Int, &first, &second),
+        },
+        BinaryOp::Sub => match (first, second) {
+            (Value::Int(one), Value::Int(two)) => Ok(Value::Int(one - two)),
+            (first, second) => mismatch(op_name, ValueType::Int, &first, &second),
+        },
+        BinaryOp::Mult => match (first, second) {
+            (Value::Int(one), Value::Int(two)) => Ok(Value::Int(one * two)),
+            (first, second) => mismatch(op_name, ValueType::Int, &first, &second),
+        },
+        // `BigInt`'s `Div`/`Rem` truncate toward zero, matching the spec's integer semantics
+        // (same convention as the old `i64` arithmetic this replaced).
+        BinaryOp::Div => match (first, second) {
+            (Value::Int(_), Value::Int(two)) if two.is_zero() => Err(EvalError::DivisionByZero),
+            (Value::Int(one), Value::Int(two)) => Ok(Value::Int(one / two)),
+            (first, second) => mismatch(op_name, ValueType::Int, &first, &second),
+        },
+        BinaryOp::Mod => match (first, second) {
+            (Value::Int(_), Value::Int(two)) if two.is_zero() => Err(EvalError::DivisionByZero),
+            (Value::Int(one), Value::Int(two)) => Ok(Value::Int(one % two)),
+            (first, second) => mismatch(op_name, ValueType::Int, &first, &second),
+        },
+        BinaryOp::Lt => match (first, second) {
+            (Value::Int(one), Value::Int(two)) => Ok(Value::Bool(one < two)),
+            (first, second) => mismatch(op_name, ValueType::Int, &first, &second),
+        },
+        BinaryOp::Gt => match (first, second) {
+            (Value::Int(one), Value::Int(two)) => Ok(Value::Bool(one > two)),
+            (first, second) => mismatch(op_name, ValueType::Int, &first, &second),
+        },
+        BinaryOp::Eq => match (first, second) {
+            (Value::Int(one), Value::Int(two)) => Ok(Value::Bool(one == two)),
+            (Value::Bool(one), Value::Bool(two)) => Ok(Value::Bool(one == two)),
+            (Value::Str(one), Value::Str(two)) => Ok(Value::Bool(one == two)),
+            (first, second) => Err(EvalError::TypeMismatch {
+                op: op_name.to_string(),
+                expected: value_type(&first),
+                actual: value_type(&second),
+            }),
+        },
+        BinaryOp::Or => match (first, second) {
+            (Value::Bool(one), Value::Bool(two)) => Ok(Value::Bool(one | two)),
+            (first, second) => mismatch(op_name, ValueType::Bool, &first, &second),
+        },
+        BinaryOp::And => match (first, second) {
+            (Value::Bool(one), Value::Bool(two)) => Ok(Value::Bool(one & two)),
+            (first, second) => mismatch(op_name, ValueType::Bool, &first, &second),
+        },
+        BinaryOp::Cat => match (first, second) {
+            (Value::Str(one), Value::Str(two)) => Ok(Value::Str(format!("{}{}", one, two))),
+            (first, second) => mismatch(op_name, ValueType::Str, &first, &second),
+        },
+        BinaryOp::Take => match (first, second) {
+            (Value::Int(one), Value::Str(two)) => {
+                let one = one.to_usize().expect("Take index out of usize range");
+                Ok(Value::Str(two[..one].to_string()))
             }
-            _ => {
-                panic!("Found non-boolean terminal")
+            (Value::Int(_), second) => Err(EvalError::TypeMismatch {
+                op: op_name.to_string(),
+                expected: ValueType::Str,
+                actual: value_type(&second),
+            }),
+            (first, second) => mismatch(op_name, ValueType::Int, &first, &second),
+        },
+        BinaryOp::Drop => match (first, second) {
+            (Value::Int(one), Value::Str(two)) => {
+                let one = one.to_usize().expect("Drop index out of usize range");
+                Ok(Value::Str(two[one..].to_string()))
             }
-        }
+            (Value::Int(_), second) => Err(EvalError::TypeMismatch {
+                op: op_name.to_string(),
+                expected: ValueType::Str,
+                actual: value_type(&second),
+            }),
+            (first, second) => mismatch(op_name, ValueType::Int, &first, &second),
+        },
+        BinaryOp::Apply => unreachable!("Apply is handled by eval_binary before reaching here"),
+    }
+}
+
+/// Renders `expr` as a single line naming its top-level constructor without recursing into its
+/// children, so a `TraceEntry` stays cheap to produce and readable even deep into a long or
+/// non-terminating reduction.
+fn render_compact(expr: &Expr) -> String {
+    match expr {
+        Expr::Value(val) => format!("Value({:?})", val),
+        Expr::Variable(Variable(id)) => format!("Variable({})", id),
+        Expr::Lambda(Lambda { body, .. }) => format!("Lambda(body={}, ..)", body),
+        Expr::If(_) => "If(..)".to_string(),
+        Expr::Unary(Unary { op, .. }) => format!("Unary({:?}, ..)", op),
+        Expr::Binary(Binary { op, .. }) => format!("Binary({:?}, ..)", op),
     }
 }
 
+/// Reports whichever operand didn't have the expected type (preferring the first).
+fn mismatch(op: &str, expected: ValueType, first: &Value, second: &Value) -> Result<Value, EvalError> {
+    let actual = if value_type(first) != expected {
+        value_type(first)
+    } else {
+        value_type(second)
+    };
+    Err(EvalError::TypeMismatch {
+        op: op.to_string(),
+        expected,
+        actual,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
@@ -278,13 +545,14 @@ mod tests {
             let parse_tree = parse_result.next().unwrap();
             let rewrites = BTreeMap::new();
             let unique_scope = Rc::new(RefCell::new(-1));
-            let ast =
-                Box::new(crate::parser::parse(parse_tree, &rewrites, unique_scope, true).unwrap());
+            let ast = Box::new(crate::parser::parse(parse_tree, &rewrites, unique_scope).unwrap());
 
             let mut executor = Executor {
                 variables: HashMap::new(),
+                next_unique_scope: -1,
+                budget: DEFAULT_BUDGET,
             };
-            let actual = executor.fully_evaluate(ast);
+            let actual = executor.fully_evaluate(ast).unwrap();
             let stringified = match actual {
                 Value::Bool(val) => serde_json::to_string(&val).unwrap(),
                 Value::Str(val) => serde_json::to_string(&val).unwrap(),
@@ -294,4 +562,117 @@ mod tests {
             assert_eq!(expected, &stringified, "input={}", input);
         }
     }
+
+    #[test]
+    fn division_by_zero_is_a_structured_error() {
+        let mut executor = Executor {
+            variables: HashMap::new(),
+            next_unique_scope: -1,
+            budget: DEFAULT_BUDGET,
+        };
+        let expr = Box::new(Expr::Binary(Binary {
+            op: BinaryOp::Div,
+            first: Box::new(Expr::Value(Value::Int(1.into()))),
+            second: Box::new(Expr::Value(Value::Int(0.into()))),
+        }));
+        assert!(matches!(
+            executor.fully_evaluate(expr),
+            Err(EvalError::DivisionByZero)
+        ));
+    }
+
+    #[test]
+    fn unbound_variable_is_a_structured_error() {
+        let mut executor = Executor {
+            variables: HashMap::new(),
+            next_unique_scope: -1,
+            budget: DEFAULT_BUDGET,
+        };
+        let expr = Box::new(Expr::Variable(Variable(42)));
+        assert!(matches!(
+            executor.fully_evaluate(expr),
+            Err(EvalError::UnboundVariable(42))
+        ));
+    }
+
+    #[test]
+    fn division_and_mod_truncate_toward_zero() {
+        let mut executor = Executor {
+            variables: HashMap::new(),
+            next_unique_scope: -1,
+            budget: DEFAULT_BUDGET,
+        };
+        let div = Box::new(Expr::Binary(Binary {
+            op: BinaryOp::Div,
+            first: Box::new(Expr::Value(Value::Int((-7).into()))),
+            second: Box::new(Expr::Value(Value::Int(2.into()))),
+        }));
+        assert_eq!(
+            Value::Int((-3).into()),
+            executor.fully_evaluate(div).unwrap()
+        );
+
+        let rem = Box::new(Expr::Binary(Binary {
+            op: BinaryOp::Mod,
+            first: Box::new(Expr::Value(Value::Int((-7).into()))),
+            second: Box::new(Expr::Value(Value::Int(2.into()))),
+        }));
+        assert_eq!(
+            Value::Int((-1).into()),
+            executor.fully_evaluate(rem).unwrap()
+        );
+    }
+
+    #[test]
+    fn addition_is_not_bounded_by_i64() {
+        let mut executor = Executor {
+            variables: HashMap::new(),
+            next_unique_scope: -1,
+            budget: DEFAULT_BUDGET,
+        };
+        let huge = num_bigint::BigInt::from(i64::MAX) * num_bigint::BigInt::from(1_000);
+        let expr = Box::new(Expr::Binary(Binary {
+            op: BinaryOp::Add,
+            first: Box::new(Expr::Value(Value::Int(huge.clone()))),
+            second: Box::new(Expr::Value(Value::Int(1.into()))),
+        }));
+        assert_eq!(
+            Value::Int(huge + num_bigint::BigInt::from(1)),
+            executor.fully_evaluate(expr).unwrap()
+        );
+    }
+
+    #[test]
+    fn reduce_with_trace_records_one_entry_per_step_and_the_final_value() {
+        let mut executor = Executor {
+            variables: HashMap::new(),
+            next_unique_scope: -1,
+            budget: DEFAULT_BUDGET,
+        };
+        let expr = Box::new(Expr::Unary(Unary {
+            op: UnaryOp::Neg,
+            val: Box::new(Expr::Value(Value::Int(1.into()))),
+        }));
+        let (result, trace) = executor.reduce_with_trace(expr, 10);
+        assert_eq!(Value::Int((-1).into()), result.unwrap());
+        assert_eq!(2, trace.len());
+        assert_eq!(0, trace[0].step);
+        assert_eq!(1, trace[1].step);
+    }
+
+    #[test]
+    fn reduce_with_trace_reports_exhausted_budget() {
+        let mut executor = Executor {
+            variables: HashMap::new(),
+            next_unique_scope: -1,
+            budget: DEFAULT_BUDGET,
+        };
+        let expr = Box::new(Expr::Unary(Unary {
+            op: UnaryOp::Neg,
+            val: Box::new(Expr::Value(Value::Int(1.into()))),
+        }));
+        let (result, trace) = executor.reduce_with_trace(expr, 0);
+        assert!(matches!(result, Err(EvalError::IterationLimitExceeded)));
+        assert_eq!(1, trace.len());
+    }
 }
@@ -0,0 +1,160 @@
+use std::rc::Rc;
+
+use im::HashMap as PersistentMap;
+use num_traits::ToPrimitive;
+
+use crate::ast::*;
+
+/// An unevaluated expression paired with the environment it should be read against, the unit of
+/// work the Krivine machine pushes onto its argument stack.
+#[derive(Clone)]
+pub struct Closure {
+    pub expr: Rc<Expr>,
+    pub env: Env,
+}
+
+impl Closure {
+    pub fn new(expr: Rc<Expr>, env: Env) -> Closure {
+        Closure { expr, env }
+    }
+
+    fn child(&self, boxed: &Expr) -> Closure {
+        Closure::new(Rc::new(boxed.clone()), self.env.clone())
+    }
+}
+
+/// Bound-variable ids never collide (`parser::parse` assigns each lambda binder a unique
+/// negative id), so the environment needs no alpha-renaming and can be a cheap persistent map.
+pub type Env = PersistentMap<i64, Closure>;
+
+/// Call-by-name abstract machine: `code` is the expression under evaluation, `env` binds its free
+/// variables to closures, and `stack` holds closures for arguments awaiting a `Lambda` to consume
+/// them.
+pub struct Krivine {
+    pub stack: Vec<Closure>,
+}
+
+impl Default for Krivine {
+    fn default() -> Krivine {
+        Krivine::new()
+    }
+}
+
+impl Krivine {
+    pub fn new() -> Krivine {
+        Krivine { stack: Vec::new() }
+    }
+
+    /// Runs a closure to a `Value`, looping over `Apply`/`Lambda`/`Variable` and forcing strict
+    /// subterms (arithmetic, comparisons, `Cat`/`Take`/`Drop`, `If`, all unaries) by recursively
+    /// running the machine on them with a fresh stack.
+    pub fn run(&mut self, closure: Closure) -> Value {
+        let mut code = closure.expr;
+        let mut env = closure.env;
+        loop {
+            match code.as_ref() {
+                Expr::Value(val) => {
+                    assert!(
+                        self.stack.is_empty(),
+                        "applied a non-function value: {:?}",
+                        val
+                    );
+                    return val.clone();
+                }
+                Expr::Lambda(Lambda { body, arg }) => {
+                    let bound = self
+                        .stack
+                        .pop()
+                        .expect("lambda entered with an empty argument stack");
+                    env = env.update(*body, bound);
+                    let next = Closure::new(arg.clone().into(), env.clone());
+                    code = next.expr;
+                }
+                Expr::Variable(Variable(id)) => {
+                    let bound = env
+                        .get(id)
+                        .unwrap_or_else(|| panic!("unbound variable: {}", id))
+                        .clone();
+                    code = bound.expr;
+                    env = bound.env;
+                }
+                Expr::Binary(Binary {
+                    op: BinaryOp::Apply,
+                    first,
+                    second,
+                }) => {
+                    let here = Closure::new(code.clone(), env.clone());
+                    self.stack.push(here.child(second));
+                    code = here.child(first).expr;
+                }
+                Expr::Binary(Binary { op, first, second }) => {
+                    let here = Closure::new(code.clone(), env.clone());
+                    let first_val = Krivine::new().run(here.child(first));
+                    let second_val = Krivine::new().run(here.child(second));
+                    return eval_strict_binary(op, first_val, second_val);
+                }
+                Expr::Unary(Unary { op, val }) => {
+                    let here = Closure::new(code.clone(), env.clone());
+                    let val = Krivine::new().run(here.child(val));
+                    return eval_strict_unary(op, val);
+                }
+                Expr::If(If {
+                    condition,
+                    if_true,
+                    if_false,
+                }) => {
+                    let here = Closure::new(code.clone(), env.clone());
+                    match Krivine::new().run(here.child(condition)) {
+                        Value::Bool(true) => {
+                            let next = here.child(if_true);
+                            code = next.expr;
+                            env = next.env;
+                        }
+                        Value::Bool(false) => {
+                            let next = here.child(if_false);
+                            code = next.expr;
+                            env = next.env;
+                        }
+                        other => panic!("non-boolean condition: {:?}", other),
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn eval_strict_unary(op: &UnaryOp, val: Value) -> Value {
+    match (op, val) {
+        (UnaryOp::Neg, Value::Int(i)) => Value::Int(-i),
+        (UnaryOp::Not, Value::Bool(b)) => Value::Bool(!b),
+        (UnaryOp::StrToInt, Value::Str(s)) => {
+            Value::Int(Value::decode_integer_body(&Value::encode_string(&s)))
+        }
+        (UnaryOp::IntToStr, Value::Int(i)) => Value::decode_string(&Value::encode_integer_body(i)),
+        (op, val) => panic!("unary operator {:?} received a mistyped value: {:?}", op, val),
+    }
+}
+
+fn eval_strict_binary(op: &BinaryOp, first: Value, second: Value) -> Value {
+    match (op, first, second) {
+        (BinaryOp::Add, Value::Int(a), Value::Int(b)) => Value::Int(a + b),
+        (BinaryOp::Sub, Value::Int(a), Value::Int(b)) => Value::Int(a - b),
+        (BinaryOp::Mult, Value::Int(a), Value::Int(b)) => Value::Int(a * b),
+        (BinaryOp::Div, Value::Int(a), Value::Int(b)) => Value::Int(a / b),
+        (BinaryOp::Mod, Value::Int(a), Value::Int(b)) => Value::Int(a % b),
+        (BinaryOp::Lt, Value::Int(a), Value::Int(b)) => Value::Bool(a < b),
+        (BinaryOp::Gt, Value::Int(a), Value::Int(b)) => Value::Bool(a > b),
+        (BinaryOp::Eq, a, b) => Value::Bool(a == b),
+        (BinaryOp::Or, Value::Bool(a), Value::Bool(b)) => Value::Bool(a | b),
+        (BinaryOp::And, Value::Bool(a), Value::Bool(b)) => Value::Bool(a & b),
+        (BinaryOp::Cat, Value::Str(a), Value::Str(b)) => Value::Str(format!("{}{}", a, b)),
+        (BinaryOp::Take, Value::Int(a), Value::Str(b)) => {
+            Value::Str(b[..a.to_usize().expect("Take index out of usize range")].to_string())
+        }
+        (BinaryOp::Drop, Value::Int(a), Value::Str(b)) => {
+            Value::Str(b[a.to_usize().expect("Drop index out of usize range")..].to_string())
+        }
+        (BinaryOp::Apply, _, _) => unreachable!("Apply is handled lazily, never forced strictly"),
+        (op, a, b) => panic!("binary operator {:?} received mistyped values: {:?}, {:?}", op, a, b),
+    }
+}
@@ -0,0 +1,202 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use num_traits::{ToPrimitive, Zero};
+
+use crate::ast::*;
+use crate::executor::{EvalError, ValueType};
+
+/// A shared memoization cell: unevaluated until first forced, then overwritten in place so every
+/// other reference to the same binding sees the cached result instead of re-deriving it.
+pub enum Thunk {
+    Unevaluated(Expr, Env),
+    Evaluated(Whnf),
+}
+
+pub type ThunkCell = Rc<RefCell<Thunk>>;
+pub type Env = HashMap<i64, ThunkCell>;
+
+/// Weak head normal form: either a terminal `Value`, or a `Lambda` still carrying the
+/// environment it closed over when it was produced.
+#[derive(Clone)]
+pub enum Whnf {
+    Value(Value),
+    Lambda { body: i64, arg: Expr, env: Env },
+}
+
+/// Call-by-need evaluator: the same reduction semantics as `Executor`, but every bound argument
+/// is wrapped in a `Thunk` that's forced at most once and shared by every reference to it, so
+/// terms that duplicate a bound variable (the Y-combinator-style recursion ICFP tasks lean on)
+/// don't re-evaluate that subtree per use. Kept alongside `Executor` so results can be
+/// cross-checked between the two backends.
+pub struct NeedExecutor;
+
+impl Default for NeedExecutor {
+    fn default() -> NeedExecutor {
+        NeedExecutor::new()
+    }
+}
+
+impl NeedExecutor {
+    pub fn new() -> NeedExecutor {
+        NeedExecutor
+    }
+
+    pub fn fully_evaluate(&mut self, expr: Expr, env: Env) -> Result<Value, EvalError> {
+        match self.eval(expr, env)? {
+            Whnf::Value(val) => Ok(val),
+            Whnf::Lambda { .. } => Err(EvalError::TypeMismatch {
+                op: "fully_evaluate".to_string(),
+                expected: ValueType::Int,
+                actual: ValueType::Fun,
+            }),
+        }
+    }
+
+    fn force(&mut self, cell: ThunkCell) -> Result<Whnf, EvalError> {
+        let pending = {
+            let borrowed = cell.borrow();
+            match &*borrowed {
+                Thunk::Evaluated(whnf) => return Ok(whnf.clone()),
+                Thunk::Unevaluated(expr, env) => (expr.clone(), env.clone()),
+            }
+        };
+        let whnf = self.eval(pending.0, pending.1)?;
+        *cell.borrow_mut() = Thunk::Evaluated(whnf.clone());
+        Ok(whnf)
+    }
+
+    fn eval_value(&mut self, expr: Expr, env: Env) -> Result<Value, EvalError> {
+        match self.eval(expr, env)? {
+            Whnf::Value(val) => Ok(val),
+            Whnf::Lambda { .. } => Err(EvalError::TypeMismatch {
+                op: "prim-op".to_string(),
+                expected: ValueType::Int,
+                actual: ValueType::Fun,
+            }),
+        }
+    }
+
+    fn eval(&mut self, expr: Expr, env: Env) -> Result<Whnf, EvalError> {
+        match expr {
+            Expr::Value(val) => Ok(Whnf::Value(val)),
+            Expr::Lambda(Lambda { body, arg }) => Ok(Whnf::Lambda {
+                body,
+                arg: *arg,
+                env,
+            }),
+            Expr::Variable(Variable(id)) => {
+                let cell = env
+                    .get(&id)
+                    .ok_or(EvalError::UnboundVariable(id))?
+                    .clone();
+                self.force(cell)
+            }
+            Expr::If(If {
+                condition,
+                if_true,
+                if_false,
+            }) => match self.eval_value(*condition, env.clone())? {
+                Value::Bool(true) => self.eval(*if_true, env),
+                Value::Bool(false) => self.eval(*if_false, env),
+                other => Err(EvalError::NonBooleanCondition(value_type(&other))),
+            },
+            Expr::Unary(Unary { op, val }) => {
+                let val = self.eval_value(*val, env)?;
+                Ok(Whnf::Value(eval_unary(&op, val)?))
+            }
+            Expr::Binary(Binary {
+                op: BinaryOp::Apply,
+                first,
+                second,
+            }) => match self.eval(*first, env.clone())? {
+                Whnf::Lambda {
+                    body,
+                    arg,
+                    env: mut closure_env,
+                } => {
+                    let arg_cell = Rc::new(RefCell::new(Thunk::Unevaluated(*second, env)));
+                    closure_env.insert(body, arg_cell);
+                    self.eval(arg, closure_env)
+                }
+                Whnf::Value(val) => Err(EvalError::TypeMismatch {
+                    op: "Apply".to_string(),
+                    expected: ValueType::Fun,
+                    actual: value_type(&val),
+                }),
+            },
+            Expr::Binary(Binary { op, first, second }) => {
+                let first_val = self.eval_value(*first, env.clone())?;
+                let second_val = self.eval_value(*second, env)?;
+                Ok(Whnf::Value(eval_strict_binary(op, first_val, second_val)?))
+            }
+        }
+    }
+}
+
+fn value_type(val: &Value) -> ValueType {
+    match val {
+        Value::Str(_) => ValueType::Str,
+        Value::Bool(_) => ValueType::Bool,
+        Value::Int(_) => ValueType::Int,
+    }
+}
+
+fn eval_unary(op: &UnaryOp, val: Value) -> Result<Value, EvalError> {
+    match (op, val) {
+        (UnaryOp::Neg, Value::Int(i)) => Ok(Value::Int(-i)),
+        (UnaryOp::Not, Value::Bool(b)) => Ok(Value::Bool(!b)),
+        (UnaryOp::StrToInt, Value::Str(s)) => Ok(Value::Int(Value::decode_integer_body(
+            &Value::encode_string(&s),
+        ))),
+        (UnaryOp::IntToStr, Value::Int(i)) => {
+            Ok(Value::decode_string(&Value::encode_integer_body(i)))
+        }
+        (op, other) => Err(EvalError::TypeMismatch {
+            op: format!("{:?}", op),
+            expected: match op {
+                UnaryOp::Neg | UnaryOp::IntToStr => ValueType::Int,
+                UnaryOp::Not => ValueType::Bool,
+                UnaryOp::StrToInt => ValueType::Str,
+            },
+            actual: value_type(&other),
+        }),
+    }
+}
+
+fn eval_strict_binary(op: BinaryOp, first: Value, second: Value) -> Result<Value, EvalError> {
+    match (&op, first, second) {
+        (BinaryOp::Add, Value::Int(a), Value::Int(b)) => Ok(Value::Int(a + b)),
+        (BinaryOp::Sub, Value::Int(a), Value::Int(b)) => Ok(Value::Int(a - b)),
+        (BinaryOp::Mult, Value::Int(a), Value::Int(b)) => Ok(Value::Int(a * b)),
+        (BinaryOp::Div, Value::Int(_), Value::Int(b)) if b.is_zero() => {
+            Err(EvalError::DivisionByZero)
+        }
+        (BinaryOp::Div, Value::Int(a), Value::Int(b)) => Ok(Value::Int(a / b)),
+        (BinaryOp::Mod, Value::Int(_), Value::Int(b)) if b.is_zero() => {
+            Err(EvalError::DivisionByZero)
+        }
+        (BinaryOp::Mod, Value::Int(a), Value::Int(b)) => Ok(Value::Int(a % b)),
+        (BinaryOp::Lt, Value::Int(a), Value::Int(b)) => Ok(Value::Bool(a < b)),
+        (BinaryOp::Gt, Value::Int(a), Value::Int(b)) => Ok(Value::Bool(a > b)),
+        (BinaryOp::Eq, a, b) => Ok(Value::Bool(a == b)),
+        (BinaryOp::Or, Value::Bool(a), Value::Bool(b)) => Ok(Value::Bool(a | b)),
+        (BinaryOp::And, Value::Bool(a), Value::Bool(b)) => Ok(Value::Bool(a & b)),
+        (BinaryOp::Cat, Value::Str(a), Value::Str(b)) => Ok(Value::Str(format!("{}{}", a, b))),
+        (BinaryOp::Take, Value::Int(a), Value::Str(b)) => {
+            let a = a.to_usize().expect("Take index out of usize range");
+            Ok(Value::Str(b[..a].to_string()))
+        }
+        (BinaryOp::Drop, Value::Int(a), Value::Str(b)) => {
+            let a = a.to_usize().expect("Drop index out of usize range");
+            Ok(Value::Str(b[a..].to_string()))
+        }
+        (BinaryOp::Apply, _, _) => unreachable!("Apply is handled lazily, never forced strictly"),
+        (op, first, second) => Err(EvalError::TypeMismatch {
+            op: op.as_str().to_string(),
+            expected: value_type(&first),
+            actual: value_type(&second),
+        }),
+    }
+}
@@ -2,6 +2,8 @@ use std::cell::RefCell;
 use std::collections::BTreeMap;
 use std::rc::Rc;
 
+use num_traits::ToPrimitive;
+
 use crate::ast::*;
 
 #[derive(pest_derive::Parser)]
@@ -81,7 +83,9 @@ pub fn parse(
         }
         Rule::lambda => {
             let mut inner = parse_tree.into_inner();
-            let id = Value::decode_integer_body(inner.next().unwrap().as_str());
+            let id = Value::decode_integer_body(inner.next().unwrap().as_str())
+                .to_i64()
+                .expect("lambda binder id out of i64 range");
             let rewrite_id = *unique_scope.borrow();
             *unique_scope.borrow_mut() = rewrite_id - 1;
             let mut rewrites = scope_rewrites.clone();
@@ -97,7 +101,9 @@ pub fn parse(
         }
         Rule::variable => {
             let mut inner = parse_tree.into_inner();
-            let source_id = Value::decode_integer_body(inner.next().unwrap().as_str());
+            let source_id = Value::decode_integer_body(inner.next().unwrap().as_str())
+                .to_i64()
+                .expect("variable id out of i64 range");
             // Rewrite scoped variables to "statically" implement capture-avoiding substitution
             let id: i64 = *scope_rewrites.get(&source_id).unwrap_or(&source_id);
             Ok(Expr::Variable(Variable(id)))
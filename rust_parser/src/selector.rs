@@ -0,0 +1,255 @@
+//! A small path/selector language for locating subterms of a decoded `Expr`, so large ASTs can
+//! be queried instead of dumped wholesale via `Command::Debug`.
+//!
+//! A selector is a `/`-separated sequence of steps:
+//!   - A node test, e.g. `binary[op=Apply]`, `lambda`, `variable[id=-3]` — matches the current
+//!     node(s) by AST variant and an optional `key=value` predicate (`op`, `id`, or `value`).
+//!   - A field step, e.g. `first`, `second`, `arg`, `condition`, `if_true`, `if_false` — navigates
+//!     into that named child of the current node(s).
+//!   - A leading empty segment (i.e. a `//` in the selector) makes the *next* node test search
+//!     all descendants of the current node(s) instead of testing the node(s) directly.
+//!
+//! For example `//binary[op=Apply]/first//variable[id=-3]` finds every `Apply` anywhere in the
+//! tree, takes each one's `first` child, and within that searches for `Variable(-3)`.
+
+use crate::ast::*;
+
+#[derive(Debug)]
+enum Predicate {
+    Op(String),
+    Id(i64),
+    Value(String),
+}
+
+#[derive(Debug)]
+enum Step {
+    Test {
+        descendant: bool,
+        kind: String,
+        predicate: Option<Predicate>,
+    },
+    Field(String),
+}
+
+pub struct Selector {
+    steps: Vec<Step>,
+}
+
+impl Selector {
+    pub fn parse(input: &str) -> anyhow::Result<Selector> {
+        let mut steps = Vec::new();
+        let mut descendant = false;
+        for part in input.split('/') {
+            if part.is_empty() {
+                descendant = true;
+                continue;
+            }
+            if let Some(open) = part.find('[') {
+                let close = part
+                    .rfind(']')
+                    .ok_or_else(|| anyhow::anyhow!("selector step `{}` is missing a closing `]`", part))?;
+                let kind = part[..open].to_string();
+                let predicate = Some(parse_predicate(&part[open + 1..close])?);
+                steps.push(Step::Test {
+                    descendant,
+                    kind,
+                    predicate,
+                });
+            } else if is_node_kind(part) {
+                steps.push(Step::Test {
+                    descendant,
+                    kind: part.to_string(),
+                    predicate: None,
+                });
+            } else {
+                steps.push(Step::Field(part.to_string()));
+            }
+            descendant = false;
+        }
+        Ok(Selector { steps })
+    }
+
+    /// Returns every matching subterm together with a human-readable path describing how it was
+    /// reached from the root.
+    pub fn select<'a>(&self, root: &'a Expr) -> Vec<(String, &'a Expr)> {
+        let mut candidates: Vec<(String, &'a Expr)> = vec![("$".to_string(), root)];
+        for step in &self.steps {
+            let mut next = Vec::new();
+            match step {
+                Step::Field(name) => {
+                    for (path, expr) in candidates {
+                        if let Some(child) = field(expr, name) {
+                            next.push((format!("{}/{}", path, name), child));
+                        }
+                    }
+                }
+                Step::Test {
+                    descendant,
+                    kind,
+                    predicate,
+                } => {
+                    for (path, expr) in candidates {
+                        if *descendant {
+                            collect_descendants(expr, &path, kind, predicate, &mut next);
+                        } else if node_matches(expr, kind, predicate) {
+                            next.push((path, expr));
+                        }
+                    }
+                }
+            }
+            candidates = next;
+        }
+        candidates
+    }
+}
+
+fn parse_predicate(raw: &str) -> anyhow::Result<Predicate> {
+    let (key, value) = raw
+        .split_once('=')
+        .ok_or_else(|| anyhow::anyhow!("malformed predicate `{}`, expected key=value", raw))?;
+    match key {
+        "op" => Ok(Predicate::Op(value.to_string())),
+        "id" => Ok(Predicate::Id(value.parse()?)),
+        "value" => Ok(Predicate::Value(value.to_string())),
+        _ => Err(anyhow::anyhow!("unknown predicate key `{}` (expected op, id, or value)", key)),
+    }
+}
+
+fn is_node_kind(name: &str) -> bool {
+    matches!(
+        name,
+        "value" | "unary" | "binary" | "lambda" | "if" | "variable"
+    )
+}
+
+fn kind_of(expr: &Expr) -> &'static str {
+    match expr {
+        Expr::Value(_) => "value",
+        Expr::Unary(_) => "unary",
+        Expr::Binary(_) => "binary",
+        Expr::Lambda(_) => "lambda",
+        Expr::If(_) => "if",
+        Expr::Variable(_) => "variable",
+    }
+}
+
+fn unary_op_str(op: &UnaryOp) -> &'static str {
+    match op {
+        UnaryOp::Neg => "-",
+        UnaryOp::Not => "!",
+        UnaryOp::StrToInt => "#",
+        UnaryOp::IntToStr => "$",
+    }
+}
+
+fn value_str(val: &Value) -> String {
+    match val {
+        Value::Str(s) => s.clone(),
+        Value::Bool(b) => b.to_string(),
+        Value::Int(i) => i.to_string(),
+    }
+}
+
+fn node_matches(expr: &Expr, kind: &str, predicate: &Option<Predicate>) -> bool {
+    if kind_of(expr) != kind {
+        return false;
+    }
+    match predicate {
+        None => true,
+        Some(Predicate::Op(op)) => match expr {
+            Expr::Binary(Binary { op: bop, .. }) => {
+                bop.as_str() == op || format!("{:?}", bop) == *op
+            }
+            Expr::Unary(Unary { op: uop, .. }) => {
+                unary_op_str(uop) == op || format!("{:?}", uop) == *op
+            }
+            _ => false,
+        },
+        Some(Predicate::Id(id)) => match expr {
+            Expr::Variable(Variable(vid)) => vid == id,
+            Expr::Lambda(Lambda { body, .. }) => body == id,
+            _ => false,
+        },
+        Some(Predicate::Value(val)) => match expr {
+            Expr::Value(v) => &value_str(v) == val,
+            _ => false,
+        },
+    }
+}
+
+/// Looks up a named direct child of `expr`.
+fn field<'a>(expr: &'a Expr, name: &str) -> Option<&'a Expr> {
+    match (expr, name) {
+        (Expr::Unary(Unary { val, .. }), "val") => Some(val),
+        (Expr::Binary(Binary { first, .. }), "first") => Some(first),
+        (Expr::Binary(Binary { second, .. }), "second") => Some(second),
+        (Expr::If(If { condition, .. }), "condition") => Some(condition),
+        (Expr::If(If { if_true, .. }), "if_true") => Some(if_true),
+        (Expr::If(If { if_false, .. }), "if_false") => Some(if_false),
+        (Expr::Lambda(Lambda { arg, .. }), "arg") => Some(arg),
+        _ => None,
+    }
+}
+
+fn children(expr: &Expr) -> Vec<&Expr> {
+    match expr {
+        Expr::Value(_) | Expr::Variable(_) => vec![],
+        Expr::Unary(Unary { val, .. }) => vec![val],
+        Expr::Binary(Binary { first, second, .. }) => vec![first, second],
+        Expr::If(If {
+            condition,
+            if_true,
+            if_false,
+        }) => vec![condition, if_true, if_false],
+        Expr::Lambda(Lambda { arg, .. }) => vec![arg],
+    }
+}
+
+fn collect_descendants<'a>(
+    expr: &'a Expr,
+    path: &str,
+    kind: &str,
+    predicate: &Option<Predicate>,
+    out: &mut Vec<(String, &'a Expr)>,
+) {
+    if node_matches(expr, kind, predicate) {
+        out.push((format!("{}//{}", path, kind), expr));
+    }
+    for (i, child) in children(expr).into_iter().enumerate() {
+        collect_descendants(child, &format!("{}/*{}", path, i), kind, predicate, out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Expr {
+        // \x. x $ 3, i.e. Lambda(body=-1, arg = Apply(Variable(-1), Value(3)))
+        Expr::Lambda(Lambda {
+            body: -1,
+            arg: Box::new(Expr::Binary(Binary {
+                op: BinaryOp::Apply,
+                first: Box::new(Expr::Variable(Variable(-1))),
+                second: Box::new(Expr::Value(Value::Int(3.into()))),
+            })),
+        })
+    }
+
+    #[test]
+    fn field_navigation_finds_lambda_body() {
+        let expr = sample();
+        let selector = Selector::parse("lambda/arg").unwrap();
+        let matches = selector.select(&expr);
+        assert_eq!(1, matches.len());
+        assert!(matches!(matches[0].1, Expr::Binary(_)));
+    }
+
+    #[test]
+    fn descendant_search_finds_variable_by_id() {
+        let expr = sample();
+        let selector = Selector::parse("//variable[id=-1]").unwrap();
+        let matches = selector.select(&expr);
+        assert_eq!(1, matches.len());
+    }
+}
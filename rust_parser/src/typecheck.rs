@@ -0,0 +1,354 @@
+//! A best-effort static type checker, run as a lint before `fully_evaluate` to surface operator
+//! type errors up front instead of mid-reduction.
+//!
+//! This is plain Hindley-Milner (Algorithm W): three base types (`Int`, `Bool`, `Str`), function
+//! types `a -> b`, and unification variables solved by a union-find-style substitution. There's no
+//! let-generalization — each `Lambda` binder just gets a fresh monomorphic type variable — since
+//! the AST has no `let` to generalize over and ICFP programs are single expressions.
+//!
+//! ICFP contest programs routinely rely on self-application (e.g. the Y combinator) to encode
+//! recursion, which is not typable in this system: inferring a type for `f` in `f f` requires
+//! unifying `a` with `a -> b`, which the occurs check always rejects. Rather than reject the whole
+//! program over that, `infer` treats an occurs-check failure at an `Apply` as untypable-but-fine:
+//! it skips that subtree (giving the application a fresh, unconstrained result type) and keeps
+//! checking the rest of the program. Genuine type mismatches elsewhere still fail the check.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::ast::*;
+
+/// An inferred type: a base type, a function type, or an as-yet-unresolved unification variable.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Type {
+    Int,
+    Bool,
+    Str,
+    Fun(Box<Type>, Box<Type>),
+    Var(usize),
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Int => write!(f, "Int"),
+            Type::Bool => write!(f, "Bool"),
+            Type::Str => write!(f, "Str"),
+            Type::Fun(arg, result) => write!(f, "({} -> {})", arg, result),
+            Type::Var(id) => write!(f, "?{}", id),
+        }
+    }
+}
+
+/// The first unification failure encountered while checking. An `OccursCheck` raised by an
+/// `Apply`'s own unification is caught by `Infer::infer` (see module docs); one raised by any
+/// other unification (e.g. of two `If` branches) is returned from `check` like a `Mismatch`.
+#[derive(Debug, PartialEq)]
+pub enum TypeError {
+    Mismatch { expected: Type, actual: Type },
+    OccursCheck,
+}
+
+impl fmt::Display for TypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeError::Mismatch { expected, actual } => {
+                write!(f, "expected type {} but found {}", expected, actual)
+            }
+            TypeError::OccursCheck => write!(f, "type variable occurs in the type it's unified with"),
+        }
+    }
+}
+
+impl std::error::Error for TypeError {}
+
+/// Infers a type for `expr`, reporting the first genuine type mismatch found. Self-application
+/// subtrees that only an occurs-check rejects (see module docs) are silently skipped rather than
+/// failing the whole program.
+pub fn check(expr: &Expr) -> Result<Type, TypeError> {
+    let mut infer = Infer::new();
+    let ty = infer.infer(expr)?;
+    Ok(infer.zonk(&ty))
+}
+
+struct Infer {
+    next_var: usize,
+    subst: HashMap<usize, Type>,
+    bindings: HashMap<i64, Type>,
+}
+
+impl Infer {
+    fn new() -> Infer {
+        Infer {
+            next_var: 0,
+            subst: HashMap::new(),
+            bindings: HashMap::new(),
+        }
+    }
+
+    fn fresh(&mut self) -> Type {
+        let id = self.next_var;
+        self.next_var += 1;
+        Type::Var(id)
+    }
+
+    /// Follows the substitution chain for a resolved unification variable, leaving everything
+    /// else (including function types whose arguments may still contain unresolved variables)
+    /// untouched.
+    fn resolve(&self, ty: &Type) -> Type {
+        match ty {
+            Type::Var(id) => match self.subst.get(id) {
+                Some(bound) => self.resolve(bound),
+                None => ty.clone(),
+            },
+            other => other.clone(),
+        }
+    }
+
+    /// Fully resolves `ty` through `self.subst`, recursing into `Type::Fun` so a resolved
+    /// function type's argument and result are also free of solved unification variables —
+    /// unlike `resolve`, which only follows the chain for `ty` itself.
+    fn zonk(&self, ty: &Type) -> Type {
+        match self.resolve(ty) {
+            Type::Fun(arg, result) => {
+                Type::Fun(Box::new(self.zonk(&arg)), Box::new(self.zonk(&result)))
+            }
+            other => other,
+        }
+    }
+
+    fn occurs_in(&self, id: usize, ty: &Type) -> bool {
+        match self.resolve(ty) {
+            Type::Var(other) => other == id,
+            Type::Fun(arg, result) => self.occurs_in(id, &arg) || self.occurs_in(id, &result),
+            Type::Int | Type::Bool | Type::Str => false,
+        }
+    }
+
+    fn unify(&mut self, expected: &Type, actual: &Type) -> Result<(), TypeError> {
+        let expected = self.resolve(expected);
+        let actual = self.resolve(actual);
+        match (&expected, &actual) {
+            (Type::Var(a), Type::Var(b)) if a == b => Ok(()),
+            (Type::Var(id), other) | (other, Type::Var(id)) => {
+                if self.occurs_in(*id, other) {
+                    return Err(TypeError::OccursCheck);
+                }
+                self.subst.insert(*id, other.clone());
+                Ok(())
+            }
+            (Type::Int, Type::Int) | (Type::Bool, Type::Bool) | (Type::Str, Type::Str) => Ok(()),
+            (Type::Fun(a1, r1), Type::Fun(a2, r2)) => {
+                self.unify(a1, a2)?;
+                self.unify(r1, r2)
+            }
+            _ => Err(TypeError::Mismatch {
+                expected,
+                actual,
+            }),
+        }
+    }
+
+    fn infer(&mut self, expr: &Expr) -> Result<Type, TypeError> {
+        match expr {
+            Expr::Value(val) => Ok(self.literal_type(val)),
+            Expr::Variable(Variable(id)) => Ok(match self.bindings.get(id) {
+                Some(ty) => ty.clone(),
+                None => self.fresh(),
+            }),
+            Expr::Lambda(Lambda { body, arg }) => {
+                let arg_ty = self.fresh();
+                self.bindings.insert(*body, arg_ty.clone());
+                let result_ty = self.infer(arg)?;
+                Ok(Type::Fun(Box::new(arg_ty), Box::new(result_ty)))
+            }
+            Expr::Unary(Unary { op, val }) => {
+                let (param, result) = unary_signature(op);
+                let val_ty = self.infer(val)?;
+                self.unify(&param, &val_ty)?;
+                Ok(result)
+            }
+            Expr::If(If {
+                condition,
+                if_true,
+                if_false,
+            }) => {
+                let condition_ty = self.infer(condition)?;
+                self.unify(&Type::Bool, &condition_ty)?;
+                let true_ty = self.infer(if_true)?;
+                let false_ty = self.infer(if_false)?;
+                self.unify(&true_ty, &false_ty)?;
+                Ok(true_ty)
+            }
+            Expr::Binary(Binary {
+                op: BinaryOp::Apply,
+                first,
+                second,
+            }) => {
+                let callee_ty = self.infer(first)?;
+                let arg_ty = self.infer(second)?;
+                let result_ty = self.fresh();
+                let callee_shape = Type::Fun(Box::new(arg_ty), Box::new(result_ty.clone()));
+                match self.unify(&callee_ty, &callee_shape) {
+                    Ok(()) => Ok(result_ty),
+                    // Self-application (e.g. the Y combinator) is untypable here; skip it.
+                    Err(TypeError::OccursCheck) => Ok(self.fresh()),
+                    Err(e) => Err(e),
+                }
+            }
+            Expr::Binary(Binary {
+                op: BinaryOp::Eq,
+                first,
+                second,
+            }) => {
+                // Eq is polymorphic: both operands must agree with each other, not with some
+                // fixed base type, so `B= S"a" S"a"` and `B= T F` typecheck just as well as
+                // `B= I1 I2`.
+                let first_ty = self.infer(first)?;
+                let second_ty = self.infer(second)?;
+                self.unify(&first_ty, &second_ty)?;
+                Ok(Type::Bool)
+            }
+            Expr::Binary(Binary { op, first, second }) => {
+                let (first_expected, second_expected, result) = binary_signature(op);
+                let first_ty = self.infer(first)?;
+                self.unify(&first_expected, &first_ty)?;
+                let second_ty = self.infer(second)?;
+                self.unify(&second_expected, &second_ty)?;
+                Ok(result)
+            }
+        }
+    }
+
+    fn literal_type(&self, val: &Value) -> Type {
+        match val {
+            Value::Int(_) => Type::Int,
+            Value::Bool(_) => Type::Bool,
+            Value::Str(_) => Type::Str,
+        }
+    }
+}
+
+fn unary_signature(op: &UnaryOp) -> (Type, Type) {
+    match op {
+        UnaryOp::Neg => (Type::Int, Type::Int),
+        UnaryOp::Not => (Type::Bool, Type::Bool),
+        UnaryOp::StrToInt => (Type::Str, Type::Int),
+        UnaryOp::IntToStr => (Type::Int, Type::Str),
+    }
+}
+
+/// The expected type of each operand and the result type for every strict `BinaryOp` other than
+/// `Apply` and `Eq`, which `Infer::infer` handles separately since both need unification variables
+/// instead of a fixed pair of operand types.
+fn binary_signature(op: &BinaryOp) -> (Type, Type, Type) {
+    match op {
+        BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mult | BinaryOp::Div | BinaryOp::Mod => {
+            (Type::Int, Type::Int, Type::Int)
+        }
+        BinaryOp::Lt | BinaryOp::Gt => (Type::Int, Type::Int, Type::Bool),
+        BinaryOp::Or | BinaryOp::And => (Type::Bool, Type::Bool, Type::Bool),
+        BinaryOp::Cat => (Type::Str, Type::Str, Type::Str),
+        BinaryOp::Take | BinaryOp::Drop => (Type::Int, Type::Str, Type::Str),
+        BinaryOp::Eq => unreachable!("Eq is handled separately in Infer::infer"),
+        BinaryOp::Apply => unreachable!("Apply is handled separately in Infer::infer"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negation_of_a_string_is_a_type_mismatch() {
+        let expr = Expr::Unary(Unary {
+            op: UnaryOp::Neg,
+            val: Box::new(Expr::Value(Value::Str("hi".to_string()))),
+        });
+        assert_eq!(
+            Err(TypeError::Mismatch {
+                expected: Type::Int,
+                actual: Type::Str,
+            }),
+            check(&expr)
+        );
+    }
+
+    #[test]
+    fn if_branches_must_agree() {
+        let expr = Expr::If(If {
+            condition: Box::new(Expr::Value(Value::Bool(true))),
+            if_true: Box::new(Expr::Value(Value::Int(1.into()))),
+            if_false: Box::new(Expr::Value(Value::Str("no".to_string()))),
+        });
+        assert!(matches!(check(&expr), Err(TypeError::Mismatch { .. })));
+    }
+
+    #[test]
+    fn lambda_application_infers_the_result_type() {
+        // (\x. x + 1) 41 : Int
+        let expr = Expr::Binary(Binary {
+            op: BinaryOp::Apply,
+            first: Box::new(Expr::Lambda(Lambda {
+                body: -1,
+                arg: Box::new(Expr::Binary(Binary {
+                    op: BinaryOp::Add,
+                    first: Box::new(Expr::Variable(Variable(-1))),
+                    second: Box::new(Expr::Value(Value::Int(1.into()))),
+                })),
+            })),
+            second: Box::new(Expr::Value(Value::Int(41.into()))),
+        });
+        assert_eq!(Ok(Type::Int), check(&expr));
+    }
+
+    #[test]
+    fn self_application_is_skipped_instead_of_rejected() {
+        // \f. f f — untypable (occurs check), but shouldn't fail the whole program.
+        let expr = Expr::Lambda(Lambda {
+            body: -1,
+            arg: Box::new(Expr::Binary(Binary {
+                op: BinaryOp::Apply,
+                first: Box::new(Expr::Variable(Variable(-1))),
+                second: Box::new(Expr::Variable(Variable(-1))),
+            })),
+        });
+        assert!(check(&expr).is_ok());
+    }
+
+    #[test]
+    fn eq_is_polymorphic_over_strings_and_bools() {
+        // B= S"a" S"a"
+        let strings = Expr::Binary(Binary {
+            op: BinaryOp::Eq,
+            first: Box::new(Expr::Value(Value::Str("a".to_string()))),
+            second: Box::new(Expr::Value(Value::Str("a".to_string()))),
+        });
+        assert_eq!(Ok(Type::Bool), check(&strings));
+
+        // B= T F
+        let bools = Expr::Binary(Binary {
+            op: BinaryOp::Eq,
+            first: Box::new(Expr::Value(Value::Bool(true))),
+            second: Box::new(Expr::Value(Value::Bool(false))),
+        });
+        assert_eq!(Ok(Type::Bool), check(&bools));
+    }
+
+    #[test]
+    fn eq_still_rejects_mismatched_operand_types() {
+        // B= I1 S"a"
+        let expr = Expr::Binary(Binary {
+            op: BinaryOp::Eq,
+            first: Box::new(Expr::Value(Value::Int(1.into()))),
+            second: Box::new(Expr::Value(Value::Str("a".to_string()))),
+        });
+        assert_eq!(
+            Err(TypeError::Mismatch {
+                expected: Type::Int,
+                actual: Type::Str,
+            }),
+            check(&expr)
+        );
+    }
+}
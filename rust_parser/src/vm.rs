@@ -0,0 +1,387 @@
+//! A bytecode compilation backend: lowers an `Expr` into a flat instruction sequence per function
+//! body ("chunk") and runs it on a small stack machine, instead of walking the `Expr` tree and
+//! re-allocating a `Box<Expr>` for every reduction the way `Executor::step` does.
+//!
+//! Semantics match the call-by-need backends (`need::NeedExecutor`): a `Lambda`'s argument is
+//! compiled into its own chunk and pushed as an unforced `Thunk`, not evaluated eagerly, so it's
+//! reduced at most once no matter how many times the bound variable is referenced. `Executor`
+//! remains the reference oracle — see the `executor_cross_check` test below — so this backend can
+//! become the default fast path for large contest inputs while staying verifiable against it.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use num_traits::{ToPrimitive, Zero};
+
+use crate::ast::*;
+use crate::executor::{EvalError, ValueType};
+
+/// One instruction. Addresses are indices into `Program::chunks`; jump targets are indices into
+/// the current chunk's own instruction vector.
+#[derive(Debug, Clone)]
+pub enum Instr {
+    PushLiteral(Value),
+    /// Forces the thunk bound to this variable id in the current frame's environment.
+    ForceVar(i64),
+    /// Captures the current environment and pushes a closure over the chunk at `body_chunk`.
+    MakeClosure { var: i64, body_chunk: usize },
+    /// Defers evaluating an `Apply`'s argument: pushes an unevaluated thunk over `arg_chunk` and
+    /// the current environment, to be forced only if the closure's body actually references it.
+    PushThunk { arg_chunk: usize },
+    /// Pops an argument thunk and a closure, binds the thunk under the closure's variable, and
+    /// enters the closure's body chunk in the extended environment.
+    Apply,
+    PrimUnary(UnaryOp),
+    PrimBinary(BinaryOp),
+    JumpIfFalse(usize),
+    Jump(usize),
+}
+
+/// A compiled program: chunk 0 is the entry point; every other chunk is the body of some `Lambda`
+/// or the deferred argument of some `Apply`, addressed by the instructions that reference it.
+#[derive(Debug)]
+pub struct Program {
+    chunks: Vec<Vec<Instr>>,
+}
+
+/// Lowers `expr` into a `Program` ready for `run`.
+pub fn compile(expr: &Expr) -> Program {
+    let mut chunks = vec![Vec::new()];
+    compile_into(expr, &mut chunks, 0);
+    Program { chunks }
+}
+
+fn compile_into(expr: &Expr, chunks: &mut Vec<Vec<Instr>>, chunk: usize) {
+    match expr {
+        Expr::Value(val) => chunks[chunk].push(Instr::PushLiteral(val.clone())),
+        Expr::Variable(Variable(id)) => chunks[chunk].push(Instr::ForceVar(*id)),
+        Expr::Unary(Unary { op, val }) => {
+            compile_into(val, chunks, chunk);
+            chunks[chunk].push(Instr::PrimUnary(op.clone()));
+        }
+        Expr::Lambda(Lambda { body, arg }) => {
+            let body_chunk = chunks.len();
+            chunks.push(Vec::new());
+            compile_into(arg, chunks, body_chunk);
+            chunks[chunk].push(Instr::MakeClosure {
+                var: *body,
+                body_chunk,
+            });
+        }
+        Expr::If(If {
+            condition,
+            if_true,
+            if_false,
+        }) => {
+            compile_into(condition, chunks, chunk);
+            let jump_if_false_at = chunks[chunk].len();
+            chunks[chunk].push(Instr::JumpIfFalse(0));
+            compile_into(if_true, chunks, chunk);
+            let jump_over_false_at = chunks[chunk].len();
+            chunks[chunk].push(Instr::Jump(0));
+            let false_branch_start = chunks[chunk].len();
+            compile_into(if_false, chunks, chunk);
+            let end = chunks[chunk].len();
+            chunks[chunk][jump_if_false_at] = Instr::JumpIfFalse(false_branch_start);
+            chunks[chunk][jump_over_false_at] = Instr::Jump(end);
+        }
+        Expr::Binary(Binary {
+            op: BinaryOp::Apply,
+            first,
+            second,
+        }) => {
+            compile_into(first, chunks, chunk);
+            let arg_chunk = chunks.len();
+            chunks.push(Vec::new());
+            compile_into(second, chunks, arg_chunk);
+            chunks[chunk].push(Instr::PushThunk { arg_chunk });
+            chunks[chunk].push(Instr::Apply);
+        }
+        Expr::Binary(Binary { op, first, second }) => {
+            compile_into(first, chunks, chunk);
+            compile_into(second, chunks, chunk);
+            chunks[chunk].push(Instr::PrimBinary(op.clone()));
+        }
+    }
+}
+
+/// A memoization cell for a `Lambda`'s argument: unevaluated until the first `ForceVar` reaches
+/// it, then overwritten with its weak head normal form like `executor::Thunk`/`need::Thunk`.
+enum Thunk {
+    Unevaluated { chunk: usize, env: Env },
+    Evaluated(Whnf),
+}
+
+type ThunkCell = Rc<RefCell<Thunk>>;
+
+/// The VM's environment: a persistent map so cloning it into a closure or a deferred argument
+/// thunk (both happen on every `Apply`) shares structure instead of copying, as in `krivine::Env`.
+/// Private, unlike `krivine::Env`: `compile`/`run` are the only public entry points and neither
+/// one needs to hand an `Env` back to the caller.
+type Env = im::HashMap<i64, ThunkCell>;
+
+/// Weak head normal form: either a terminal `Value`, or a closure still carrying the environment
+/// it captured when it was made.
+#[derive(Clone)]
+enum Whnf {
+    Value(Value),
+    Closure { var: i64, body_chunk: usize, env: Env },
+}
+
+/// A stack slot: most instructions operate on already-forced `Whnf`s, but `Apply`'s argument is
+/// pushed as a still-unevaluated `Thunk` so it's only forced if the callee actually demands it.
+enum Slot {
+    Whnf(Whnf),
+    Thunk(ThunkCell),
+}
+
+struct Vm<'p> {
+    program: &'p Program,
+}
+
+impl<'p> Vm<'p> {
+    fn run_chunk(&self, chunk: usize, env: &Env) -> Result<Whnf, EvalError> {
+        let code = &self.program.chunks[chunk];
+        let mut stack: Vec<Slot> = Vec::new();
+        let mut pc = 0;
+        while pc < code.len() {
+            match &code[pc] {
+                Instr::PushLiteral(val) => stack.push(Slot::Whnf(Whnf::Value(val.clone()))),
+                Instr::ForceVar(id) => {
+                    let cell = env
+                        .get(id)
+                        .ok_or(EvalError::UnboundVariable(*id))?
+                        .clone();
+                    stack.push(Slot::Whnf(self.force(cell)?));
+                }
+                Instr::MakeClosure { var, body_chunk } => {
+                    stack.push(Slot::Whnf(Whnf::Closure {
+                        var: *var,
+                        body_chunk: *body_chunk,
+                        env: env.clone(),
+                    }));
+                }
+                Instr::PushThunk { arg_chunk } => {
+                    stack.push(Slot::Thunk(Rc::new(RefCell::new(Thunk::Unevaluated {
+                        chunk: *arg_chunk,
+                        env: env.clone(),
+                    }))));
+                }
+                Instr::Apply => {
+                    let arg = pop(&mut stack);
+                    let callee = pop(&mut stack);
+                    let arg_cell = match arg {
+                        Slot::Thunk(cell) => cell,
+                        Slot::Whnf(whnf) => Rc::new(RefCell::new(Thunk::Evaluated(whnf))),
+                    };
+                    match callee {
+                        Slot::Whnf(Whnf::Closure {
+                            var,
+                            body_chunk,
+                            env: closure_env,
+                        }) => {
+                            let mut call_env = closure_env;
+                            call_env.insert(var, arg_cell);
+                            stack.push(Slot::Whnf(self.run_chunk(body_chunk, &call_env)?));
+                        }
+                        Slot::Whnf(Whnf::Value(val)) => {
+                            return Err(EvalError::TypeMismatch {
+                                op: "Apply".to_string(),
+                                expected: ValueType::Fun,
+                                actual: value_type(&val),
+                            })
+                        }
+                        Slot::Thunk(_) => unreachable!("Apply's callee slot is never a thunk"),
+                    }
+                }
+                Instr::PrimUnary(op) => {
+                    let val = self.pop_value(&mut stack)?;
+                    stack.push(Slot::Whnf(Whnf::Value(eval_unary(op, val)?)));
+                }
+                Instr::PrimBinary(op) => {
+                    let second = self.pop_value(&mut stack)?;
+                    let first = self.pop_value(&mut stack)?;
+                    stack.push(Slot::Whnf(Whnf::Value(eval_strict_binary(
+                        op.clone(),
+                        first,
+                        second,
+                    )?)));
+                }
+                Instr::JumpIfFalse(target) => match self.pop_value(&mut stack)? {
+                    Value::Bool(false) => {
+                        pc = *target;
+                        continue;
+                    }
+                    Value::Bool(true) => (),
+                    other => return Err(EvalError::NonBooleanCondition(value_type(&other))),
+                },
+                Instr::Jump(target) => {
+                    pc = *target;
+                    continue;
+                }
+            }
+            pc += 1;
+        }
+        match pop(&mut stack) {
+            Slot::Whnf(whnf) => Ok(whnf),
+            Slot::Thunk(_) => unreachable!("a chunk always leaves a forced Whnf on top"),
+        }
+    }
+
+    /// Forces a thunk to weak head normal form, memoizing the result in place so every other
+    /// reference to the same binding reuses it instead of re-running its chunk.
+    fn force(&self, cell: ThunkCell) -> Result<Whnf, EvalError> {
+        let pending = match &*cell.borrow() {
+            Thunk::Evaluated(whnf) => return Ok(whnf.clone()),
+            Thunk::Unevaluated { chunk, env } => (*chunk, env.clone()),
+        };
+        let whnf = self.run_chunk(pending.0, &pending.1)?;
+        *cell.borrow_mut() = Thunk::Evaluated(whnf.clone());
+        Ok(whnf)
+    }
+
+    fn pop_value(&self, stack: &mut Vec<Slot>) -> Result<Value, EvalError> {
+        let whnf = match pop(stack) {
+            Slot::Whnf(whnf) => whnf,
+            Slot::Thunk(cell) => self.force(cell)?,
+        };
+        match whnf {
+            Whnf::Value(val) => Ok(val),
+            Whnf::Closure { .. } => Err(EvalError::TypeMismatch {
+                op: "prim-op".to_string(),
+                expected: ValueType::Int,
+                actual: ValueType::Fun,
+            }),
+        }
+    }
+}
+
+fn pop(stack: &mut Vec<Slot>) -> Slot {
+    stack.pop().expect("compiled chunk underflowed its stack")
+}
+
+/// Runs a compiled `Program` to completion, returning the terminal `Value` it reduces to.
+pub fn run(program: &Program) -> Result<Value, EvalError> {
+    let vm = Vm { program };
+    match vm.run_chunk(0, &Env::new())? {
+        Whnf::Value(val) => Ok(val),
+        // An unapplied closure at the top level never reduces further, just like `Executor`
+        // looping on an unchanging `Expr::Lambda` until its budget runs out.
+        Whnf::Closure { .. } => Err(EvalError::IterationLimitExceeded),
+    }
+}
+
+fn value_type(val: &Value) -> ValueType {
+    match val {
+        Value::Str(_) => ValueType::Str,
+        Value::Bool(_) => ValueType::Bool,
+        Value::Int(_) => ValueType::Int,
+    }
+}
+
+fn eval_unary(op: &UnaryOp, val: Value) -> Result<Value, EvalError> {
+    match (op, val) {
+        (UnaryOp::Neg, Value::Int(i)) => Ok(Value::Int(-i)),
+        (UnaryOp::Not, Value::Bool(b)) => Ok(Value::Bool(!b)),
+        (UnaryOp::StrToInt, Value::Str(s)) => Ok(Value::Int(Value::decode_integer_body(
+            &Value::encode_string(&s),
+        ))),
+        (UnaryOp::IntToStr, Value::Int(i)) => {
+            Ok(Value::decode_string(&Value::encode_integer_body(i)))
+        }
+        (op, other) => Err(EvalError::TypeMismatch {
+            op: format!("{:?}", op),
+            expected: match op {
+                UnaryOp::Neg | UnaryOp::IntToStr => ValueType::Int,
+                UnaryOp::Not => ValueType::Bool,
+                UnaryOp::StrToInt => ValueType::Str,
+            },
+            actual: value_type(&other),
+        }),
+    }
+}
+
+fn eval_strict_binary(op: BinaryOp, first: Value, second: Value) -> Result<Value, EvalError> {
+    match (&op, first, second) {
+        (BinaryOp::Add, Value::Int(a), Value::Int(b)) => Ok(Value::Int(a + b)),
+        (BinaryOp::Sub, Value::Int(a), Value::Int(b)) => Ok(Value::Int(a - b)),
+        (BinaryOp::Mult, Value::Int(a), Value::Int(b)) => Ok(Value::Int(a * b)),
+        (BinaryOp::Div, Value::Int(_), Value::Int(b)) if b.is_zero() => {
+            Err(EvalError::DivisionByZero)
+        }
+        (BinaryOp::Div, Value::Int(a), Value::Int(b)) => Ok(Value::Int(a / b)),
+        (BinaryOp::Mod, Value::Int(_), Value::Int(b)) if b.is_zero() => {
+            Err(EvalError::DivisionByZero)
+        }
+        (BinaryOp::Mod, Value::Int(a), Value::Int(b)) => Ok(Value::Int(a % b)),
+        (BinaryOp::Lt, Value::Int(a), Value::Int(b)) => Ok(Value::Bool(a < b)),
+        (BinaryOp::Gt, Value::Int(a), Value::Int(b)) => Ok(Value::Bool(a > b)),
+        (BinaryOp::Eq, a, b) => Ok(Value::Bool(a == b)),
+        (BinaryOp::Or, Value::Bool(a), Value::Bool(b)) => Ok(Value::Bool(a | b)),
+        (BinaryOp::And, Value::Bool(a), Value::Bool(b)) => Ok(Value::Bool(a & b)),
+        (BinaryOp::Cat, Value::Str(a), Value::Str(b)) => Ok(Value::Str(format!("{}{}", a, b))),
+        (BinaryOp::Take, Value::Int(a), Value::Str(b)) => {
+            let a = a.to_usize().expect("Take index out of usize range");
+            Ok(Value::Str(b[..a].to_string()))
+        }
+        (BinaryOp::Drop, Value::Int(a), Value::Str(b)) => {
+            let a = a.to_usize().expect("Drop index out of usize range");
+            Ok(Value::Str(b[a..].to_string()))
+        }
+        (BinaryOp::Apply, _, _) => unreachable!("Apply is compiled to its own instructions"),
+        (op, first, second) => Err(EvalError::TypeMismatch {
+            op: op.as_str().to_string(),
+            expected: value_type(&first),
+            actual: value_type(&second),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::collections::BTreeMap;
+    use std::collections::HashMap;
+    use std::fs;
+    use std::rc::Rc;
+
+    use lazy_static::lazy_static;
+
+    use super::*;
+
+    lazy_static! {
+        static ref INPUTS: Vec<String> = fs::read_to_string("tests/inputs.icfp")
+            .unwrap()
+            .lines()
+            .filter(|s| !s.starts_with('#'))
+            .map(|s| s.trim().to_string())
+            .collect();
+    }
+
+    fn parse(input: &str) -> Expr {
+        let mut parse_result =
+            <crate::parser::ICFPParser as pest::Parser<_>>::parse(crate::parser::Rule::expr, input)
+                .unwrap();
+        let parse_tree = parse_result.next().unwrap();
+        crate::parser::parse(parse_tree, &BTreeMap::new(), Rc::new(RefCell::new(-1))).unwrap()
+    }
+
+    #[test]
+    fn executor_cross_check() {
+        for input in INPUTS.iter() {
+            let ast = parse(input);
+
+            let mut executor = crate::executor::Executor {
+                variables: HashMap::new(),
+                next_unique_scope: -1,
+                budget: crate::executor::DEFAULT_BUDGET,
+            };
+            let expected = executor.fully_evaluate(Box::new(ast.clone())).unwrap();
+
+            let program = compile(&ast);
+            let actual = run(&program).unwrap();
+
+            assert_eq!(expected, actual, "input={}", input);
+        }
+    }
+}